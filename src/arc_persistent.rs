@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+// `third`'s persistent list with `Arc` in place of `Rc`, so versions of a
+// list (and the tails they share) can be handed to other threads. Nothing is
+// ever mutated once it's shared, so the atomic refcount is the only
+// synchronisation needed; the list is `Send` and `Sync` whenever `T` is both,
+// exactly like `Arc<T>`. The code itself is `third`'s: see `persistent_list`.
+crate::third::persistent_list!(Arc);
+
+#[cfg(test)]
+mod test {
+    use super::List;
+    use crate::test_util::Drops;
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    #[test]
+    fn basics() {
+        let list = List::new();
+        assert_eq!(list.head(), None);
+
+        let list = list.append(1).append(2).append(3);
+        assert_eq!(list.head(), Some(&3));
+
+        let list = list.tail();
+        assert_eq!(list.head(), Some(&2));
+
+        let list = list.tail();
+        assert_eq!(list.head(), Some(&1));
+
+        let list = list.tail();
+        assert_eq!(list.head(), None);
+
+        // Make sure empty tail works
+        let list = list.tail();
+        assert_eq!(list.head(), None);
+    }
+
+    #[test]
+    fn iter() {
+        let list = List::new().append(1).append(2).append(3);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn long_list_drops_without_recursing() {
+        let list: List<_> = (0..1_000_000).collect();
+        let shared = list.tail();
+        drop(list);
+        assert_eq!(shared.head(), Some(&999_998));
+    }
+
+    // Every thread builds its own versions on top of one shared suffix and
+    // reads the suffix through them, then drops everything it made. The
+    // suffix must survive all of that untouched, and be freed exactly once
+    // at the end.
+    #[test]
+    fn threads_share_a_suffix() {
+        const THREADS: usize = 8;
+        const SUFFIX: usize = 1_000;
+
+        let drops = Drops::new();
+        let suffix: List<_> = (0..SUFFIX).map(|_| drops.track()).collect();
+        let barrier = Arc::new(Barrier::new(THREADS));
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let suffix = suffix.clone();
+                let drops = drops.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..100 {
+                        let mut version = suffix.clone();
+                        for _ in 0..=t + i % 5 {
+                            version = version.append(drops.track());
+                        }
+                        assert_eq!(version.iter().count(), SUFFIX + t + i % 5 + 1);
+                        let mut rest = version.clone();
+                        for _ in 0..=t + i % 5 {
+                            rest = rest.tail();
+                        }
+                        // what's left is the shared suffix itself, not a copy
+                        assert!(std::ptr::eq(rest.head().unwrap(), suffix.head().unwrap()));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let per_thread = |t: usize| (0..100).map(|i| t + i % 5 + 1).sum::<usize>();
+        let made: usize = (0..THREADS).map(per_thread).sum();
+        assert_eq!(drops.dropped(), made);
+        assert_eq!(suffix.iter().count(), SUFFIX);
+
+        drop(suffix);
+        assert_eq!(drops.dropped(), made + SUFFIX);
+        assert_eq!(drops.live(), 0);
+    }
+
+    // Threads let go of versions sharing one long suffix all at once. However
+    // the drops interleave, the suffix has to be freed by a loop, not by
+    // recursing down a million nodes on some thread's stack.
+    #[test]
+    fn racing_drops_of_a_long_suffix() {
+        const THREADS: usize = 4;
+
+        for _ in 0..5 {
+            let suffix: List<_> = (0..1_000_000).collect();
+            let barrier = Arc::new(Barrier::new(THREADS));
+            let handles: Vec<_> = (0..THREADS)
+                .map(|t| {
+                    let version = suffix.append(t);
+                    let barrier = barrier.clone();
+                    thread::spawn(move || {
+                        barrier.wait();
+                        drop(version);
+                    })
+                })
+                .collect();
+            drop(suffix);
+            for handle in handles {
+                handle.join().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn send_and_sync() {
+        fn send_sync<T: Send + Sync>(_: &T) {}
+        send_sync(&List::<i32>::new());
+        send_sync(&List::new().append(String::new()));
+    }
+}
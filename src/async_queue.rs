@@ -0,0 +1,357 @@
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use crate::fifth;
+
+// `fifth`'s queue behind a lock, for handing values to async tasks. Pushing
+// never waits; popping returns a future that resolves once there's something
+// to pop, or to None once the queue is closed and drained.
+//
+// Consumers that find the queue empty leave their `Waker` in `waiters`, and
+// every push wakes all of them. Waking just one would be cheaper, but if that
+// one's future has been dropped in the meantime the wakeup is lost and
+// another consumer sleeps forever.
+pub struct List<T> {
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    queue: fifth::List<T>,
+    waiters: fifth::List<Waker>,
+    closed: bool,
+}
+
+// Called with the lock released: a waker is free to poll its task right
+// there, or to touch the queue some other way, and either would want the
+// lock back.
+fn wake_all(mut waiters: fifth::List<Waker>) {
+    while let Some(waker) = waiters.pop() {
+        waker.wake();
+    }
+}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        List {
+            state: Mutex::new(State {
+                queue: fifth::List::new(),
+                waiters: fifth::List::new(),
+                closed: false,
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap()
+    }
+
+    // Hands the element back if the queue has been closed.
+    pub fn push(&self, elem: T) -> Result<(), T> {
+        let waiters = {
+            let mut state = self.lock();
+            if state.closed {
+                return Err(elem);
+            }
+            state.queue.push(elem);
+            mem::take(&mut state.waiters)
+        };
+        wake_all(waiters);
+        Ok(())
+    }
+
+    pub fn try_pop(&self) -> Option<T> {
+        self.lock().queue.pop()
+    }
+
+    // Resolves to the next element, or None once the queue is closed and
+    // drained.
+    pub fn recv(&self) -> Recv<'_, T> {
+        Recv { list: self }
+    }
+
+    // The `Stream::poll_next` shape, for driving the queue by hand.
+    pub fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut state = self.lock();
+        if let Some(elem) = state.queue.pop() {
+            return Poll::Ready(Some(elem));
+        }
+        if state.closed {
+            return Poll::Ready(None);
+        }
+        // A future polled again before anything was pushed is already in
+        // the list; don't let spurious polls pile up copies of its waker.
+        if !state.waiters.iter().any(|w| w.will_wake(cx.waker())) {
+            state.waiters.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+
+    // Wakes every pending `recv`; they drain what's left, then see None.
+    pub fn close(&self) {
+        let waiters = {
+            let mut state = self.lock();
+            state.closed = true;
+            mem::take(&mut state.waiters)
+        };
+        wake_all(waiters);
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().queue.is_empty()
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct Recv<'a, T> {
+    list: &'a List<T>,
+}
+
+impl<T> Future for Recv<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.list.poll_next(cx)
+    }
+}
+
+// Just enough of an executor to use the queue without pulling in a runtime:
+// poll the future on this thread, and park between polls until its waker
+// unparks us.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{block_on, List};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicBool, Ordering::*};
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn basics() {
+        let list = List::new();
+        assert_eq!(list.try_pop(), None);
+
+        list.push(1).unwrap();
+        list.push(2).unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(block_on(list.recv()), Some(1));
+        assert_eq!(block_on(list.recv()), Some(2));
+        assert!(list.is_empty());
+
+        list.push(3).unwrap();
+        list.close();
+        assert!(list.is_closed());
+        assert_eq!(list.push(4), Err(4));
+        assert_eq!(block_on(list.recv()), Some(3));
+        assert_eq!(block_on(list.recv()), None);
+    }
+
+    // A waker that just records that it was woken.
+    struct Flag(AtomicBool);
+
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, SeqCst);
+        }
+    }
+
+    #[test]
+    fn registers_waker() {
+        let list = List::new();
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+        let waker = Waker::from(flag.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut recv = list.recv();
+        assert_eq!(Pin::new(&mut recv).poll(&mut cx), Poll::Pending);
+        // polling again doesn't register twice
+        assert_eq!(Pin::new(&mut recv).poll(&mut cx), Poll::Pending);
+        assert_eq!(list.lock().waiters.len(), 1);
+        assert!(!flag.0.load(SeqCst));
+
+        list.push(1).unwrap();
+        assert!(flag.0.load(SeqCst));
+        assert_eq!(list.lock().waiters.len(), 0);
+        assert_eq!(Pin::new(&mut recv).poll(&mut cx), Poll::Ready(Some(1)));
+
+        flag.0.store(false, SeqCst);
+        assert_eq!(list.poll_next(&mut cx), Poll::Pending);
+        list.close();
+        assert!(flag.0.load(SeqCst));
+        assert_eq!(list.poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    // A waker that goes straight back to the queue, as an executor polling
+    // inline from `wake` would.
+    struct Reentrant(Arc<List<i32>>, AtomicBool);
+
+    impl Wake for Reentrant {
+        fn wake(self: Arc<Self>) {
+            self.0.is_empty();
+            self.1.store(true, SeqCst);
+        }
+    }
+
+    #[test]
+    fn wakers_run_without_the_lock() {
+        let list = Arc::new(List::new());
+        let reentrant = Arc::new(Reentrant(list.clone(), AtomicBool::new(false)));
+        let waker = Waker::from(reentrant.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(list.poll_next(&mut cx), Poll::Pending);
+        list.push(1).unwrap();
+        assert!(reentrant.1.swap(false, SeqCst));
+
+        assert_eq!(list.poll_next(&mut cx), Poll::Ready(Some(1)));
+        assert_eq!(list.poll_next(&mut cx), Poll::Pending);
+        list.close();
+        assert!(reentrant.1.load(SeqCst));
+    }
+
+    #[test]
+    fn wakes_across_threads() {
+        let list = Arc::new(List::new());
+        let producer = {
+            let list = list.clone();
+            thread::spawn(move || {
+                for i in 0..100 {
+                    if i % 10 == 0 {
+                        thread::sleep(Duration::from_millis(1));
+                    }
+                    list.push(i).unwrap();
+                }
+                list.close();
+            })
+        };
+
+        let received = block_on(async {
+            let mut received = Vec::new();
+            while let Some(elem) = list.recv().await {
+                received.push(elem);
+            }
+            received
+        });
+        producer.join().unwrap();
+        assert_eq!(received, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn dropped_recv_does_not_swallow_wakeup() {
+        let list = Arc::new(List::new());
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+        let waker = Waker::from(flag);
+        let mut cx = Context::from_waker(&waker);
+
+        // a consumer registers, then gives up on its recv
+        {
+            let mut abandoned = list.recv();
+            assert_eq!(Pin::new(&mut abandoned).poll(&mut cx), Poll::Pending);
+        }
+
+        let consumer = {
+            let list = list.clone();
+            thread::spawn(move || block_on(list.recv()))
+        };
+        thread::sleep(Duration::from_millis(20));
+        list.push(7).unwrap();
+        assert_eq!(consumer.join().unwrap(), Some(7));
+    }
+
+    // Hands control back to the executor once, so two tasks on one thread
+    // actually interleave.
+    struct YieldNow(bool);
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    // Runs two tasks to completion on one thread.
+    async fn join<A: Future<Output = ()>, B: Future<Output = ()>>(a: A, b: B) {
+        let mut a = Some(Box::pin(a));
+        let mut b = Some(Box::pin(b));
+        std::future::poll_fn(|cx| {
+            if a.as_mut().is_some_and(|a| a.as_mut().poll(cx).is_ready()) {
+                a = None;
+            }
+            if b.as_mut().is_some_and(|b| b.as_mut().poll(cx).is_ready()) {
+                b = None;
+            }
+            if a.is_none() && b.is_none() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    #[test]
+    fn between_tasks() {
+        let list = List::new();
+        let mut received = Vec::new();
+        block_on(join(
+            async {
+                for i in 0..10 {
+                    list.push(i).unwrap();
+                    YieldNow(false).await;
+                }
+                list.close();
+            },
+            async {
+                while let Some(elem) = list.recv().await {
+                    received.push(elem);
+                }
+            },
+        ));
+        assert_eq!(received, (0..10).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,343 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::fifth;
+
+// `fifth`'s queue behind a lock, with a capacity and two condition variables
+// so producers can sleep while it's full and consumers while it's empty.
+//
+// Closing wakes everyone up. After that pushes fail straight away, but pops
+// keep going until whatever was already queued has been drained.
+pub struct List<T> {
+    state: Mutex<State<T>>,
+    capacity: usize,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+struct State<T> {
+    queue: fifth::List<T>,
+    closed: bool,
+}
+
+// The element that couldn't be pushed comes back in the error.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PushError<T> {
+    Full(T),
+    Closed(T),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PopError {
+    Empty,
+    Closed,
+}
+
+impl<T> List<T> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "a blocking queue needs room for one element");
+        List {
+            state: Mutex::new(State {
+                queue: fifth::List::new(),
+                closed: false,
+            }),
+            capacity,
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap()
+    }
+
+    // Blocks while the queue is full. Only fails if the queue is closed.
+    pub fn push(&self, elem: T) -> Result<(), PushError<T>> {
+        let state = self.lock();
+        let state = self
+            .not_full
+            .wait_while(state, |state| {
+                !state.closed && state.queue.len() == self.capacity
+            })
+            .unwrap();
+        self.push_locked(state, elem)
+    }
+
+    pub fn try_push(&self, elem: T) -> Result<(), PushError<T>> {
+        let state = self.lock();
+        self.push_locked(state, elem)
+    }
+
+    // Blocks for at most `timeout` while the queue is full, then gives up
+    // with `Full`.
+    pub fn push_timeout(&self, elem: T, timeout: Duration) -> Result<(), PushError<T>> {
+        let state = self.lock();
+        let (state, _) = self
+            .not_full
+            .wait_timeout_while(state, timeout, |state| {
+                !state.closed && state.queue.len() == self.capacity
+            })
+            .unwrap();
+        self.push_locked(state, elem)
+    }
+
+    fn push_locked(
+        &self,
+        mut state: MutexGuard<'_, State<T>>,
+        elem: T,
+    ) -> Result<(), PushError<T>> {
+        if state.closed {
+            return Err(PushError::Closed(elem));
+        }
+        if state.queue.len() == self.capacity {
+            return Err(PushError::Full(elem));
+        }
+        state.queue.push(elem);
+        drop(state);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    // Blocks while the queue is empty. Returns None once the queue is closed
+    // and drained.
+    pub fn pop(&self) -> Option<T> {
+        let state = self.lock();
+        let state = self
+            .not_empty
+            .wait_while(state, |state| !state.closed && state.queue.is_empty())
+            .unwrap();
+        self.pop_locked(state).ok()
+    }
+
+    pub fn try_pop(&self) -> Result<T, PopError> {
+        let state = self.lock();
+        self.pop_locked(state)
+    }
+
+    // Blocks for at most `timeout` while the queue is empty, then gives up
+    // with `Empty`.
+    pub fn pop_timeout(&self, timeout: Duration) -> Result<T, PopError> {
+        let state = self.lock();
+        let (state, _) = self
+            .not_empty
+            .wait_timeout_while(state, timeout, |state| {
+                !state.closed && state.queue.is_empty()
+            })
+            .unwrap();
+        self.pop_locked(state)
+    }
+
+    fn pop_locked(&self, mut state: MutexGuard<'_, State<T>>) -> Result<T, PopError> {
+        match state.queue.pop() {
+            Some(elem) => {
+                drop(state);
+                self.not_full.notify_one();
+                Ok(elem)
+            }
+            None if state.closed => Err(PopError::Closed),
+            None => Err(PopError::Empty),
+        }
+    }
+
+    // Wakes every blocked push and pop; closing twice is harmless.
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().queue.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<T> fmt::Display for PushError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PushError::Full(_) => write!(f, "pushing onto a full queue"),
+            PushError::Closed(_) => write!(f, "pushing onto a closed queue"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> Error for PushError<T> {}
+
+impl fmt::Display for PopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PopError::Empty => write!(f, "popping from an empty queue"),
+            PopError::Closed => write!(f, "popping from a closed, drained queue"),
+        }
+    }
+}
+
+impl Error for PopError {}
+
+#[cfg(test)]
+mod test {
+    use super::{List, PopError, PushError};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn basics() {
+        let list = List::new(3);
+        assert_eq!(list.capacity(), 3);
+        assert_eq!(list.try_pop(), Err(PopError::Empty));
+
+        list.push(1).unwrap();
+        list.push(2).unwrap();
+        list.try_push(3).unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.try_push(4), Err(PushError::Full(4)));
+
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(list.try_pop(), Ok(2));
+        list.try_push(4).unwrap();
+        assert_eq!(list.pop(), Some(3));
+        assert_eq!(list.pop(), Some(4));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn timeouts() {
+        let list = List::new(1);
+        let start = Instant::now();
+        assert_eq!(
+            list.pop_timeout(Duration::from_millis(20)),
+            Err(PopError::Empty)
+        );
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        list.push(1).unwrap();
+        let start = Instant::now();
+        assert_eq!(
+            list.push_timeout(2, Duration::from_millis(20)),
+            Err(PushError::Full(2))
+        );
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        assert_eq!(list.pop_timeout(Duration::from_secs(1)), Ok(1));
+        assert_eq!(list.push_timeout(3, Duration::from_secs(1)), Ok(()));
+    }
+
+    #[test]
+    fn blocks_until_room() {
+        let list = Arc::new(List::new(1));
+        list.push(1).unwrap();
+
+        let pusher = {
+            let list = list.clone();
+            thread::spawn(move || list.push(2))
+        };
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(pusher.join().unwrap(), Ok(()));
+        assert_eq!(list.pop(), Some(2));
+
+        let popper = {
+            let list = list.clone();
+            thread::spawn(move || list.pop())
+        };
+        thread::sleep(Duration::from_millis(20));
+        list.push(3).unwrap();
+        assert_eq!(popper.join().unwrap(), Some(3));
+    }
+
+    #[test]
+    fn close_wakes_everyone() {
+        let empty = Arc::new(List::<i32>::new(1));
+        let full = Arc::new(List::new(1));
+        full.push(0).unwrap();
+
+        let poppers: Vec<_> = (0..3)
+            .map(|_| {
+                let empty = empty.clone();
+                thread::spawn(move || empty.pop())
+            })
+            .collect();
+        let pushers: Vec<_> = (1..4)
+            .map(|i| {
+                let full = full.clone();
+                thread::spawn(move || full.push(i))
+            })
+            .collect();
+        thread::sleep(Duration::from_millis(20));
+
+        empty.close();
+        full.close();
+        for popper in poppers {
+            assert_eq!(popper.join().unwrap(), None);
+        }
+        for pusher in pushers {
+            assert!(matches!(pusher.join().unwrap(), Err(PushError::Closed(_))));
+        }
+        assert!(full.is_closed());
+        assert_eq!(full.try_push(5), Err(PushError::Closed(5)));
+
+        // Closing doesn't throw away what was already queued
+        assert_eq!(full.pop(), Some(0));
+        assert_eq!(full.pop(), None);
+        assert_eq!(full.try_pop(), Err(PopError::Closed));
+        assert_eq!(
+            full.pop_timeout(Duration::from_secs(1)),
+            Err(PopError::Closed)
+        );
+    }
+
+    #[test]
+    fn producers_and_consumers() {
+        const PRODUCERS: usize = 4;
+        const PER_PRODUCER: usize = 5_000;
+
+        let list = Arc::new(List::new(16));
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|p| {
+                let list = list.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        list.push((p, i)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        let consumers: Vec<_> = (0..4)
+            .map(|_| {
+                let list = list.clone();
+                thread::spawn(move || {
+                    let mut last = [None; PRODUCERS];
+                    let mut count = 0;
+                    while let Some((p, i)) = list.pop() {
+                        assert!(last[p] < Some(i), "producer {} out of order", p);
+                        last[p] = Some(i);
+                        count += 1;
+                    }
+                    count
+                })
+            })
+            .collect();
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        list.close();
+        let total: usize = consumers.into_iter().map(|c| c.join().unwrap()).sum();
+        assert_eq!(total, PRODUCERS * PER_PRODUCER);
+    }
+}
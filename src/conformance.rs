@@ -0,0 +1,370 @@
+//! Behavioural checks that any list implementing the crate's traits should
+//! pass. Every check panics on the first mismatch, so they can be called
+//! straight from a `#[test]`, or stamped out one test per check with
+//! [`stack_conformance!`](crate::stack_conformance),
+//! [`queue_conformance!`](crate::queue_conformance) and
+//! [`deque_conformance!`](crate::deque_conformance).
+//!
+//! The checks only ever push `i32`s; they're about ordering, not about `T`.
+//! The macros expand to a nested module, so the list type handed to them
+//! should be spelled as an absolute path (`crate::...` or `::some_crate::...`).
+
+/// Checks for anything implementing [`Stack`](crate::Stack).
+pub mod stack {
+    use crate::traits::Stack;
+
+    /// Elements come back out in the reverse order they went in.
+    pub fn lifo<S: Stack<i32> + Default>() {
+        let mut stack = S::default();
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.pop(), Some(2));
+
+        // Push some more just to make sure nothing's corrupted
+        stack.push(4);
+        stack.push(5);
+        assert_eq!(stack.pop(), Some(5));
+        assert_eq!(stack.pop(), Some(4));
+        assert_eq!(stack.pop(), Some(1));
+    }
+
+    /// An empty stack keeps saying so, no matter how often it's asked.
+    pub fn exhaustion<S: Stack<i32> + Default>() {
+        let mut stack = S::default();
+        assert!(stack.is_empty());
+        assert_eq!(stack.len(), 0);
+        assert_eq!(stack.pop(), None);
+        assert_eq!(stack.pop(), None);
+
+        stack.push(1);
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.pop(), None);
+        assert!(stack.is_empty());
+        assert!(stack.peek().is_none());
+    }
+
+    /// A stack that has been drained behaves like a fresh one.
+    pub fn reuse_after_exhaustion<S: Stack<i32> + Default>() {
+        let mut stack = S::default();
+        for round in 0..3 {
+            for i in 0..round + 2 {
+                stack.push(i);
+            }
+            for i in (0..round + 2).rev() {
+                assert_eq!(stack.pop(), Some(i));
+            }
+            assert_eq!(stack.pop(), None);
+        }
+    }
+
+    /// `peek` and `len` always agree with what `pop` is about to do.
+    pub fn peek_consistency<S: Stack<i32> + Default>() {
+        let mut stack = S::default();
+        assert!(stack.peek().is_none());
+        for i in 0..5 {
+            stack.push(i);
+            assert_eq!(stack.peek().as_deref(), Some(&i));
+            assert_eq!(stack.len(), i as usize + 1);
+        }
+        while !stack.is_empty() {
+            let peeked = stack.peek().map(|elem| *elem);
+            let len = stack.len();
+            assert_eq!(stack.pop(), peeked);
+            assert_eq!(stack.len(), len - 1);
+        }
+        assert!(stack.peek().is_none());
+    }
+
+    /// Iterating yields the elements in the order `pop` would.
+    pub fn iter_order<S>()
+    where
+        S: Stack<i32> + Default + IntoIterator<Item = i32>,
+    {
+        let stack = S::default();
+        assert_eq!(stack.into_iter().next(), None);
+
+        let mut stack = S::default();
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![3, 2, 1]);
+    }
+}
+
+/// Checks for anything implementing [`Queue`](crate::Queue).
+pub mod queue {
+    use crate::traits::Queue;
+
+    /// Elements come back out in the order they went in.
+    pub fn fifo<Q: Queue<i32> + Default>() {
+        let mut queue = Q::default();
+        queue.enqueue(1);
+        queue.enqueue(2);
+        queue.enqueue(3);
+        assert_eq!(queue.dequeue(), Some(1));
+        assert_eq!(queue.dequeue(), Some(2));
+
+        // Push some more just to make sure nothing's corrupted
+        queue.enqueue(4);
+        queue.enqueue(5);
+        assert_eq!(queue.dequeue(), Some(3));
+        assert_eq!(queue.dequeue(), Some(4));
+        assert_eq!(queue.dequeue(), Some(5));
+    }
+
+    /// An empty queue keeps saying so, no matter how often it's asked.
+    pub fn exhaustion<Q: Queue<i32> + Default>() {
+        let mut queue = Q::default();
+        assert!(queue.is_empty());
+        assert_eq!(queue.len(), 0);
+        assert_eq!(queue.dequeue(), None);
+        assert_eq!(queue.dequeue(), None);
+
+        queue.enqueue(1);
+        assert_eq!(queue.dequeue(), Some(1));
+        assert_eq!(queue.dequeue(), None);
+        assert!(queue.is_empty());
+        assert!(queue.front().is_none());
+    }
+
+    /// A queue that has been drained behaves like a fresh one. This is the
+    /// case that catches a tail pointer left dangling by the last `dequeue`.
+    pub fn reuse_after_exhaustion<Q: Queue<i32> + Default>() {
+        let mut queue = Q::default();
+        for round in 0..3 {
+            for i in 0..round + 2 {
+                queue.enqueue(i);
+            }
+            for i in 0..round + 2 {
+                assert_eq!(queue.dequeue(), Some(i));
+            }
+            assert_eq!(queue.dequeue(), None);
+        }
+    }
+
+    /// `front` and `len` always agree with what `dequeue` is about to do.
+    pub fn peek_consistency<Q: Queue<i32> + Default>() {
+        let mut queue = Q::default();
+        assert!(queue.front().is_none());
+        for i in 0..5 {
+            queue.enqueue(i);
+            assert_eq!(queue.front().as_deref(), Some(&0));
+            assert_eq!(queue.len(), i as usize + 1);
+        }
+        while !queue.is_empty() {
+            let peeked = queue.front().map(|elem| *elem);
+            let len = queue.len();
+            assert_eq!(queue.dequeue(), peeked);
+            assert_eq!(queue.len(), len - 1);
+        }
+        assert!(queue.front().is_none());
+    }
+
+    /// Iterating yields the elements in the order `dequeue` would.
+    pub fn iter_order<Q>()
+    where
+        Q: Queue<i32> + Default + IntoIterator<Item = i32>,
+    {
+        let queue = Q::default();
+        assert_eq!(queue.into_iter().next(), None);
+
+        let mut queue = Q::default();
+        queue.enqueue(1);
+        queue.enqueue(2);
+        queue.enqueue(3);
+        assert_eq!(queue.into_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+}
+
+/// Checks for anything implementing [`Deque`](crate::Deque), on top of the
+/// stack and queue checks it should also pass.
+pub mod deque {
+    use crate::traits::Deque;
+
+    /// Each end on its own behaves like a stack.
+    pub fn each_end<D: Deque<i32> + Default>() {
+        let mut deque = D::default();
+        deque.push_front(1);
+        deque.push_front(2);
+        assert_eq!(deque.pop_front(), Some(2));
+        assert_eq!(deque.pop_front(), Some(1));
+        assert_eq!(deque.pop_front(), None);
+
+        deque.push_back(1);
+        deque.push_back(2);
+        assert_eq!(deque.pop_back(), Some(2));
+        assert_eq!(deque.pop_back(), Some(1));
+        assert_eq!(deque.pop_back(), None);
+    }
+
+    /// What goes in one end comes out the other in order.
+    pub fn across_ends<D: Deque<i32> + Default>() {
+        let mut deque = D::default();
+        deque.push_front(1);
+        deque.push_front(2);
+        deque.push_front(3);
+        assert_eq!(deque.pop_back(), Some(1));
+        assert_eq!(deque.pop_back(), Some(2));
+        assert_eq!(deque.pop_back(), Some(3));
+        assert_eq!(deque.pop_back(), None);
+
+        deque.push_back(1);
+        deque.push_back(2);
+        deque.push_back(3);
+        assert_eq!(deque.pop_front(), Some(1));
+        assert_eq!(deque.pop_front(), Some(2));
+        assert_eq!(deque.pop_front(), Some(3));
+        assert_eq!(deque.pop_front(), None);
+    }
+
+    /// Both peeks always agree with what the matching pop is about to do,
+    /// including when there is only one element for both ends to share.
+    pub fn peek_consistency<D: Deque<i32> + Default>() {
+        let mut deque = D::default();
+        assert!(deque.peek_front().is_none());
+        assert!(deque.peek_back().is_none());
+
+        deque.push_back(1);
+        assert_eq!(deque.peek_front().as_deref(), Some(&1));
+        assert_eq!(deque.peek_back().as_deref(), Some(&1));
+
+        deque.push_front(0);
+        deque.push_back(2);
+        assert_eq!(deque.len(), 3);
+        assert_eq!(deque.peek_front().as_deref(), Some(&0));
+        assert_eq!(deque.peek_back().as_deref(), Some(&2));
+
+        let back = deque.peek_back().map(|elem| *elem);
+        assert_eq!(deque.pop_back(), back);
+        let front = deque.peek_front().map(|elem| *elem);
+        assert_eq!(deque.pop_front(), front);
+        assert_eq!(deque.peek_front().as_deref(), Some(&1));
+        assert_eq!(deque.peek_back().as_deref(), Some(&1));
+        assert_eq!(deque.pop_back(), Some(1));
+        assert!(deque.is_empty());
+    }
+
+    /// Iterating runs front to back, and backwards from the back.
+    pub fn iter_order<D>()
+    where
+        D: Deque<i32> + Default + IntoIterator<Item = i32>,
+        D::IntoIter: DoubleEndedIterator,
+    {
+        let mut deque = D::default();
+        deque.push_back(2);
+        deque.push_front(1);
+        deque.push_back(3);
+
+        let mut iter = deque.into_iter();
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.next_back(), Some(2));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+}
+
+/// Generates a module named `$name` with one `#[test]` per stack check,
+/// run against `$list`. `$list` must implement `Stack<i32>`, `Default` and
+/// `IntoIterator<Item = i32>`.
+#[macro_export]
+macro_rules! stack_conformance {
+    ($name:ident, $list:ty) => {
+        mod $name {
+            #[test]
+            fn lifo() {
+                $crate::conformance::stack::lifo::<$list>();
+            }
+
+            #[test]
+            fn exhaustion() {
+                $crate::conformance::stack::exhaustion::<$list>();
+            }
+
+            #[test]
+            fn reuse_after_exhaustion() {
+                $crate::conformance::stack::reuse_after_exhaustion::<$list>();
+            }
+
+            #[test]
+            fn peek_consistency() {
+                $crate::conformance::stack::peek_consistency::<$list>();
+            }
+
+            #[test]
+            fn iter_order() {
+                $crate::conformance::stack::iter_order::<$list>();
+            }
+        }
+    };
+}
+
+/// Generates a module named `$name` with one `#[test]` per queue check,
+/// run against `$list`. `$list` must implement `Queue<i32>`, `Default` and
+/// `IntoIterator<Item = i32>`.
+#[macro_export]
+macro_rules! queue_conformance {
+    ($name:ident, $list:ty) => {
+        mod $name {
+            #[test]
+            fn fifo() {
+                $crate::conformance::queue::fifo::<$list>();
+            }
+
+            #[test]
+            fn exhaustion() {
+                $crate::conformance::queue::exhaustion::<$list>();
+            }
+
+            #[test]
+            fn reuse_after_exhaustion() {
+                $crate::conformance::queue::reuse_after_exhaustion::<$list>();
+            }
+
+            #[test]
+            fn peek_consistency() {
+                $crate::conformance::queue::peek_consistency::<$list>();
+            }
+
+            #[test]
+            fn iter_order() {
+                $crate::conformance::queue::iter_order::<$list>();
+            }
+        }
+    };
+}
+
+/// Generates a module named `$name` with one `#[test]` per stack, queue and
+/// deque check, run against `$list`. `$list` must implement `Deque<i32>`,
+/// `Default` and `IntoIterator<Item = i32>` with a double-ended iterator.
+#[macro_export]
+macro_rules! deque_conformance {
+    ($name:ident, $list:ty) => {
+        mod $name {
+            $crate::stack_conformance!(stack, $list);
+            $crate::queue_conformance!(queue, $list);
+
+            #[test]
+            fn each_end() {
+                $crate::conformance::deque::each_end::<$list>();
+            }
+
+            #[test]
+            fn across_ends() {
+                $crate::conformance::deque::across_ends::<$list>();
+            }
+
+            #[test]
+            fn peek_consistency() {
+                $crate::conformance::deque::peek_consistency::<$list>();
+            }
+
+            #[test]
+            fn iter_order() {
+                $crate::conformance::deque::iter_order::<$list>();
+            }
+        }
+    };
+}
@@ -1,5 +1,13 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::mem;
 use std::ptr;
 
+use crate::traits::{Collection, Queue};
+
+#[allow(dead_code)]
 mod bad {
     pub struct List<'a, T> {
         head: Link<T>,
@@ -109,34 +117,86 @@ mod bad {
     // }
 }
 
+// Every link is a raw pointer, and nodes are only turned back into a `Box`
+// when they're popped or dropped. Mixing the two (a `Box` chain from `head`
+// plus a raw `tail` into the last box) doesn't work: moving a `Box` asserts
+// unique ownership of the node, which invalidates any raw pointer derived
+// from it earlier, so `tail` would be dangling as far as the aliasing model
+// is concerned. With raw pointers all the way down, every pointer to a node
+// comes from the same `Box::into_raw` and stays valid until it's freed.
 pub struct List<T> {
     head: Link<T>,
-    tail: *mut Node<T>, // DANGER DANGER
+    tail: Link<T>,
+    len: usize,
 }
 
-type Link<T> = Option<Box<Node<T>>>;
+type Link<T> = *mut Node<T>;
 
 struct Node<T> {
     elem: T,
     next: Link<T>,
 }
 
+// The raw pointers opt us out of Send and Sync, but they only ever point into
+// nodes the list owns, so it's as thread-safe as a Box.
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Sync> Sync for List<T> {}
+
 pub struct IntoIter<T>(List<T>);
 
 pub struct Iter<'a, T> {
     next: Option<&'a Node<T>>,
+    len: usize,
 }
 
 pub struct IterMut<'a, T> {
     next: Option<&'a mut Node<T>>,
+    len: usize,
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<T> Collection<T> for List<T> {
+    type Ref<'a>
+        = &'a T
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Queue<T> for List<T> {
+    fn enqueue(&mut self, elem: T) {
+        self.push(elem)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn front(&self) -> Option<&T> {
+        self.peek()
+    }
 }
 
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
-        let mut cur_link = self.head.take();
-        while let Some(mut boxed_node) = cur_link {
-            cur_link = boxed_node.next.take();
-        }
+        while self.pop().is_some() {}
     }
 }
 
@@ -145,90 +205,247 @@ impl<T> Iterator for IntoIter<T> {
     fn next(&mut self) -> Option<Self::Item> {
         self.0.pop()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
 }
 
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.next.map(|node| {
-            self.next = node.next.as_deref();
+            self.next = unsafe { node.next.as_ref() };
+            self.len -= 1;
             &node.elem
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
 impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.next.take().map(|node| {
-            self.next = node.next.as_deref_mut();
+            self.next = unsafe { node.next.as_mut() };
+            self.len -= 1;
             &mut node.elem
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
 impl<T> List<T> {
     pub fn new() -> Self {
         List {
-            head: None,
+            head: ptr::null_mut(),
             tail: ptr::null_mut(),
+            len: 0,
         }
     }
 
     pub fn push(&mut self, elem: T) {
-        let mut new_tail = Box::new(Node { elem, next: None });
-
-        let raw_tail: *mut _ = &mut *new_tail;
+        let new_tail = Box::into_raw(Box::new(Node {
+            elem,
+            next: ptr::null_mut(),
+        }));
 
-        // .is_null checks for null, equivalent to checking for None
         if !self.tail.is_null() {
             unsafe {
                 // If the old tail existed, update it to point to the new tail
-                (*self.tail).next = Some(new_tail);
+                (*self.tail).next = new_tail;
             }
         } else {
             // Otherwise, update the head to point to it
-            self.head = Some(new_tail);
+            self.head = new_tail;
         }
 
-        self.tail = raw_tail;
+        self.tail = new_tail;
+        self.len += 1;
     }
 
     pub fn pop(&mut self) -> Option<T> {
-        self.head.take().map(|head| {
-            let head = *head;
-            self.head = head.next;
+        if self.head.is_null() {
+            return None;
+        }
+        let head = unsafe { Box::from_raw(self.head) };
+        self.head = head.next;
 
-            if self.head.is_none() {
-                self.tail = ptr::null_mut();
-            }
+        if self.head.is_null() {
+            self.tail = ptr::null_mut();
+        }
 
-            head.elem
-        })
+        self.len -= 1;
+        Some(head.elem)
     }
+
     pub fn peek(&self) -> Option<&T> {
-        self.head.as_ref().map(|node| &node.elem)
+        unsafe { self.head.as_ref().map(|node| &node.elem) }
     }
 
     pub fn peek_mut(&mut self) -> Option<&mut T> {
-        self.head.as_mut().map(|node| &mut node.elem)
+        unsafe { self.head.as_mut().map(|node| &mut node.elem) }
     }
 
-    pub fn into_iter(self) -> IntoIter<T> {
-        IntoIter(self)
+    pub fn peek_back(&self) -> Option<&T> {
+        unsafe { self.tail.as_ref().map(|node| &node.elem) }
+    }
+
+    pub fn peek_back_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.tail.as_mut().map(|node| &mut node.elem) }
+    }
+
+    // Moves all of `other`'s elements to the back of this queue, leaving
+    // `other` empty. O(1): it's just relinking our tail to their head.
+    pub fn append(&mut self, other: &mut List<T>) {
+        if other.head.is_null() {
+            return;
+        }
+        if self.tail.is_null() {
+            self.head = other.head;
+        } else {
+            unsafe { (*self.tail).next = other.head };
+        }
+        self.tail = other.tail;
+        self.len += other.len;
+        other.head = ptr::null_mut();
+        other.tail = ptr::null_mut();
+        other.len = 0;
+    }
+
+    // Splits off everything from index `at` back, keeping the first `at`
+    // elements. Panics if `at > len`. O(at), to find where to cut.
+    pub fn split_off(&mut self, at: usize) -> List<T> {
+        assert!(at <= self.len, "split_off index out of bounds");
+        if at == 0 {
+            return mem::take(self);
+        }
+        let mut last = self.head;
+        for _ in 1..at {
+            last = unsafe { (*last).next };
+        }
+        let rest = List {
+            head: unsafe { mem::replace(&mut (*last).next, ptr::null_mut()) },
+            tail: if at == self.len {
+                ptr::null_mut()
+            } else {
+                self.tail
+            },
+            len: self.len - at,
+        };
+        self.tail = last;
+        self.len = at;
+        rest
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
     pub fn iter(&self) -> Iter<'_, T> {
         Iter {
-            next: self.head.as_deref(),
+            next: unsafe { self.head.as_ref() },
+            len: self.len,
         }
     }
 
     pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut {
-            next: self.head.as_deref_mut(),
+            next: unsafe { self.head.as_mut() },
+            len: self.len,
+        }
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for List<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elem in self {
+            elem.hash(state);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut List<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
         }
     }
 }
@@ -236,6 +453,10 @@ impl<T> List<T> {
 #[cfg(test)]
 mod test {
     use super::List;
+    use crate::test_util::hash;
+
+    crate::queue_conformance!(conformance, crate::queue::List<i32>);
+
     #[test]
     fn basics() {
         let mut list = List::new();
@@ -315,4 +536,278 @@ mod test {
         assert_eq!(iter.next(), Some(&mut 3));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn std_traits() {
+        let list: List<i32> = vec![1, 2, 3].into_iter().collect();
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+
+        let mut copy = list.clone();
+        assert_eq!(copy, list);
+        assert_eq!(hash(&copy), hash(&list));
+
+        // Check the clone has its own working tail
+        copy.push(4);
+        assert_ne!(copy, list);
+        assert!(copy > list);
+        assert_eq!(copy.pop(), Some(1));
+        assert!(copy > list);
+        copy.extend(vec![5, 6]);
+        assert_eq!(format!("{:?}", copy), "[2, 3, 4, 5, 6]");
+        assert_eq!(List::<i32>::default(), List::new());
+
+        for elem in &mut copy {
+            *elem *= 2;
+        }
+        let mut elems = Vec::new();
+        for elem in &copy {
+            elems.push(*elem);
+        }
+        assert_eq!(elems, vec![4, 6, 8, 10, 12]);
+    }
+
+    #[test]
+    fn len() {
+        let mut list = List::new();
+        assert_eq!(list.len(), 0);
+        assert!(list.is_empty());
+
+        list.push(1);
+        list.push(2);
+        list.push(3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().len(), 3);
+        assert_eq!(list.iter_mut().len(), 3);
+
+        let mut iter = list.iter();
+        iter.next();
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+
+        list.pop();
+        list.pop();
+        list.pop();
+        list.pop();
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.iter().len(), 0);
+
+        list.push(4);
+        list.push(5);
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 2);
+        iter.next();
+        assert_eq!(iter.len(), 1);
+    }
+
+    #[test]
+    fn peek_back() {
+        let mut list = List::new();
+        assert_eq!(list.peek_back(), None);
+        assert_eq!(list.peek_back_mut(), None);
+
+        list.push(1);
+        assert_eq!(list.peek_back(), list.peek());
+        list.push(2);
+        list.push(3);
+        assert_eq!(list.peek_back(), Some(&3));
+
+        *list.peek_back_mut().unwrap() *= 10;
+        list.pop();
+        list.pop();
+        assert_eq!(list.peek(), Some(&30));
+        assert_eq!(list.pop(), Some(30));
+        assert_eq!(list.peek_back(), None);
+    }
+
+    #[test]
+    fn append() {
+        let mut list = List::new();
+        let mut other = List::new();
+        list.append(&mut other);
+        assert!(list.is_empty());
+
+        other.push(1);
+        other.push(2);
+        list.append(&mut other);
+        assert_eq!(other.peek(), None);
+        assert_eq!(other.peek_back(), None);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2]);
+
+        other.push(3);
+        other.push(4);
+        list.append(&mut other);
+        list.append(&mut List::new());
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.peek_back(), Some(&4));
+
+        // Check both tails still work after the splice
+        list.push(5);
+        other.push(6);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(other.pop(), Some(6));
+        assert_eq!(other.pop(), None);
+    }
+
+    #[test]
+    fn split_off() {
+        let mut list: List<_> = (1..=4).collect();
+
+        let mut rest = list.split_off(4);
+        assert!(rest.is_empty());
+        rest.push(9);
+        assert_eq!(rest.pop(), Some(9));
+
+        let mut rest = list.split_off(2);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2]);
+        assert_eq!(rest.iter().collect::<Vec<_>>(), vec![&3, &4]);
+        assert_eq!((list.len(), rest.len()), (2, 2));
+
+        // both halves have a working tail
+        list.push(5);
+        rest.push(6);
+        assert_eq!(list.peek_back(), Some(&5));
+        assert_eq!(rest.peek_back(), Some(&6));
+
+        let all = list.split_off(0);
+        assert!(list.is_empty());
+        assert_eq!(all.iter().collect::<Vec<_>>(), vec![&1, &2, &5]);
+        list.push(7);
+        assert_eq!(list.peek_back(), Some(&7));
+    }
+
+    #[test]
+    #[should_panic(expected = "split_off index out of bounds")]
+    fn split_off_out_of_bounds() {
+        let mut list: List<_> = (1..=2).collect();
+        list.split_off(3);
+    }
+
+    // Small enough to run under Miri, which is what they're for:
+    //
+    //     cargo +nightly miri test fifth::test::miri
+    //
+    // Each one interleaves operations so that every pointer the list keeps
+    // (head, tail, and each node's next) is used after the others have been
+    // written through. Boxed elements make leaks and double frees visible.
+    mod miri {
+        use super::List;
+
+        #[test]
+        fn push_pop_peek_mut() {
+            let mut list = List::new();
+            list.push(Box::new(1));
+            list.push(Box::new(2));
+            *list.peek_mut().unwrap().as_mut() *= 10;
+            assert_eq!(list.pop().as_deref(), Some(&10));
+            list.push(Box::new(3));
+            *list.peek_mut().unwrap().as_mut() += 1;
+            assert_eq!(list.peek().map(|elem| **elem), Some(3));
+            assert_eq!(list.pop().as_deref(), Some(&3));
+            assert_eq!(list.pop().as_deref(), Some(&3));
+            assert!(list.peek_mut().is_none());
+            assert!(list.pop().is_none());
+        }
+
+        // Draining the list nulls `tail`; the next push must not write
+        // through the freed node it used to point at.
+        #[test]
+        fn reuse_after_emptying() {
+            let mut list = List::new();
+            for round in 0..3 {
+                list.push(Box::new(round));
+                assert_eq!(list.pop().as_deref(), Some(&round));
+                assert!(list.pop().is_none());
+            }
+            list.push(Box::new(7));
+            list.push(Box::new(8));
+            assert_eq!(list.iter().map(|elem| **elem).collect::<Vec<_>>(), [7, 8]);
+        }
+
+        // `iter_mut` reborrows nodes that `tail` also points at; pushing
+        // afterwards has to go through a `tail` that's still usable.
+        #[test]
+        fn iter_mut_then_push() {
+            let mut list = List::new();
+            list.push(Box::new(1));
+            list.push(Box::new(2));
+            for elem in list.iter_mut() {
+                **elem += 1;
+            }
+            list.push(Box::new(4));
+            *list.peek_mut().unwrap().as_mut() += 10;
+            for elem in list.iter_mut() {
+                **elem *= 2;
+            }
+            assert_eq!(
+                list.iter().map(|elem| **elem).collect::<Vec<_>>(),
+                [24, 6, 8]
+            );
+        }
+
+        #[test]
+        fn interleaved() {
+            let mut list = List::new();
+            list.push(Box::new(1));
+            list.push(Box::new(2));
+            list.push(Box::new(3));
+
+            assert_eq!(list.pop().as_deref(), Some(&1));
+            list.push(Box::new(4));
+            assert_eq!(list.pop().as_deref(), Some(&2));
+            list.push(Box::new(5));
+
+            assert_eq!(list.peek().map(|elem| **elem), Some(3));
+            list.push(Box::new(6));
+            *list.peek_mut().unwrap().as_mut() *= 10;
+            assert_eq!(list.peek().map(|elem| **elem), Some(30));
+            assert_eq!(list.pop().as_deref(), Some(&30));
+
+            for elem in list.iter_mut() {
+                **elem *= 100;
+            }
+            assert_eq!(list.peek().map(|elem| **elem), Some(400));
+
+            assert_eq!(
+                list.into_iter().map(|elem| *elem).collect::<Vec<_>>(),
+                [400, 500, 600]
+            );
+        }
+
+        // Splicing hands one list's nodes, and its tail, to another; both
+        // lists have to be able to keep pushing afterwards.
+        #[test]
+        fn append_and_split_off() {
+            let mut list = List::new();
+            let mut other = List::new();
+            list.push(Box::new(1));
+            other.push(Box::new(2));
+            other.push(Box::new(3));
+            list.append(&mut other);
+            **list.peek_back_mut().unwrap() += 10;
+            other.push(Box::new(4));
+
+            let mut rest = list.split_off(1);
+            list.push(Box::new(5));
+            rest.push(Box::new(6));
+            **rest.peek_mut().unwrap() += 10;
+            list.append(&mut rest);
+            assert_eq!(
+                list.iter().map(|elem| **elem).collect::<Vec<_>>(),
+                [1, 5, 12, 13, 6]
+            );
+            assert_eq!(other.pop().as_deref(), Some(&4));
+        }
+
+        // Whatever is still queued gets freed exactly once, including after
+        // the list has been cloned and partly consumed.
+        #[test]
+        fn drop_leftovers() {
+            let mut list: List<_> = (0..4).map(Box::new).collect();
+            let copy = list.clone();
+            list.pop();
+            let mut iter = copy.into_iter();
+            iter.next();
+            drop(iter);
+            drop(list);
+        }
+    }
 }
@@ -1,7 +1,10 @@
 use std::mem;
 
+use crate::traits::{Collection, Stack};
+
 // List a = Empty | Elem a (List a)
 
+#[allow(dead_code)]
 mod bad1 {
 
     #[derive(Debug)]
@@ -17,6 +20,7 @@ mod bad1 {
     // [Elem A, ptr] -> (Elem B, ptr) -> (Empty, *junk*)
 }
 
+#[allow(dead_code)]
 mod bad2 {
     // really bad idea
     #[derive(Debug)]
@@ -129,6 +133,43 @@ impl List {
     }
 }
 
+impl Collection<i32> for List {
+    type Ref<'a> = &'a i32;
+
+    fn len(&self) -> usize {
+        let mut len = 0;
+        let mut cur_link = &self.head;
+        while let Link::More(node) = cur_link {
+            len += 1;
+            cur_link = &node.next;
+        }
+        len
+    }
+}
+
+impl Stack<i32> for List {
+    fn push(&mut self, elem: i32) {
+        self.push(elem)
+    }
+
+    fn pop(&mut self) -> Option<i32> {
+        self.pop()
+    }
+
+    fn peek(&self) -> Option<&i32> {
+        match &self.head {
+            Link::Empty => None,
+            Link::More(node) => Some(&node.elem),
+        }
+    }
+}
+
+impl Default for List {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // impl Drop for List {
 //     fn drop(&mut self) {
 //         // NOTE: you can't actually explicitly call `drop` in real Rust code;
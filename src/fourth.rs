@@ -1,19 +1,34 @@
 use std::cell::Ref;
 use std::cell::RefCell;
 use std::cell::RefMut;
-use std::rc::Rc;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+use crate::traits::{Collection, Deque, Queue, Stack};
 
 pub struct List<T> {
     head: Link<T>,
     tail: Link<T>,
+    len: usize,
+    // Tells apart handles from different lists; see `NodeHandle`.
+    id: usize,
 }
 
+// Only the `next` links own their nodes; `prev` links are weak, so the nodes
+// form a plain chain from `head` rather than a ring of `Rc`s keeping each
+// other alive. Dropping the chain anywhere frees everything after that point,
+// whether or not `List::drop` gets to run.
 type Link<T> = Option<Rc<RefCell<Node<T>>>>;
+type WeakLink<T> = Option<Weak<RefCell<Node<T>>>>;
 
 struct Node<T> {
     elem: T,
     next: Link<T>,
-    prev: Link<T>,
+    prev: WeakLink<T>,
 }
 
 impl<T> Node<T> {
@@ -28,17 +43,23 @@ impl<T> Node<T> {
 
 impl<T> List<T> {
     pub fn new() -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
         List {
             head: None,
             tail: None,
+            len: 0,
+            id: NEXT_ID.fetch_add(1, Relaxed),
         }
     }
 
     pub fn push_front(&mut self, elem: T) {
-        let new_head = Node::new(elem);
+        self.link_front(Node::new(elem));
+    }
+
+    fn link_front(&mut self, new_head: Rc<RefCell<Node<T>>>) {
         match self.head.take() {
             Some(old_head) => {
-                old_head.borrow_mut().prev = Some(new_head.clone());
+                old_head.borrow_mut().prev = Some(Rc::downgrade(&new_head));
                 new_head.borrow_mut().next = Some(old_head);
                 self.head = Some(new_head);
             }
@@ -47,6 +68,7 @@ impl<T> List<T> {
                 self.head = Some(new_head);
             }
         }
+        self.len += 1;
     }
 
     pub fn pop_front(&mut self) -> Option<T> {
@@ -67,6 +89,7 @@ impl<T> List<T> {
             // old_head.borrow_mut().elem
             // old_head.into_inner().elem
             // Rc::try_unwrap(old_head).unwrap().into_inner().elem
+            self.len -= 1;
             Rc::try_unwrap(old_head).ok().unwrap().into_inner().elem
         })
     }
@@ -76,7 +99,7 @@ impl<T> List<T> {
     //     self.head.as_ref().map(|node| &node.borrow().elem)
     // }
 
-    pub fn peek_front(&self) -> Option<Ref<T>> {
+    pub fn peek_front(&self) -> Option<Ref<'_, T>> {
         // self.head.as_ref().map(|node| node.borrow())
         self.head
             .as_ref()
@@ -84,11 +107,14 @@ impl<T> List<T> {
     }
 
     pub fn push_back(&mut self, elem: T) {
-        let new_tail = Node::new(elem);
+        self.link_back(Node::new(elem));
+    }
+
+    fn link_back(&mut self, new_tail: Rc<RefCell<Node<T>>>) {
         match self.tail.take() {
             Some(old_tail) => {
                 old_tail.borrow_mut().next = Some(new_tail.clone());
-                new_tail.borrow_mut().prev = Some(old_tail);
+                new_tail.borrow_mut().prev = Some(Rc::downgrade(&old_tail));
                 self.tail = Some(new_tail);
             }
             None => {
@@ -96,12 +122,16 @@ impl<T> List<T> {
                 self.tail = Some(new_tail);
             }
         }
+        self.len += 1;
     }
 
     pub fn pop_back(&mut self) -> Option<T> {
         self.tail.take().map(|old_tail| {
             match old_tail.borrow_mut().prev.take() {
                 Some(new_tail) => {
+                    // `old_tail` is still linked in, so its predecessor is
+                    // too, and the upgrade can't fail.
+                    let new_tail = new_tail.upgrade().unwrap();
                     new_tail.borrow_mut().next.take();
                     self.tail = Some(new_tail);
                 }
@@ -109,29 +139,242 @@ impl<T> List<T> {
                     self.head.take();
                 }
             }
+            self.len -= 1;
             Rc::try_unwrap(old_tail).ok().unwrap().into_inner().elem
         })
     }
 
-    pub fn peek_back(&self) -> Option<Ref<T>> {
+    pub fn peek_back(&self) -> Option<Ref<'_, T>> {
         self.tail
             .as_ref()
             .map(|node| Ref::map(node.borrow(), |node| &node.elem))
     }
 
-    pub fn peek_back_mut(&mut self) -> Option<RefMut<T>> {
+    pub fn peek_back_mut(&mut self) -> Option<RefMut<'_, T>> {
         self.tail
             .as_ref()
             .map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.elem))
     }
 
-    pub fn peek_front_mut(&mut self) -> Option<RefMut<T>> {
+    pub fn peek_front_mut(&mut self) -> Option<RefMut<'_, T>> {
         self.head
             .as_ref()
             .map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.elem))
     }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+// A handle to one node, for getting at it again later without a search.
+//
+// It's only a `Weak` to the node, so it never keeps an element alive: once the
+// node is popped or removed, every handle to it goes stale and operations
+// through it do nothing. Handles remember which list made them, and using one
+// with any other list is a bug that panics rather than tearing that list's
+// links apart.
+pub struct NodeHandle<T> {
+    node: Weak<RefCell<Node<T>>>,
+    list: usize,
+}
+
+impl<T> Clone for NodeHandle<T> {
+    fn clone(&self) -> Self {
+        NodeHandle {
+            node: self.node.clone(),
+            list: self.list,
+        }
+    }
 }
 
+impl<T> NodeHandle<T> {
+    // False once the node has left its list.
+    pub fn is_live(&self) -> bool {
+        self.node.strong_count() > 0
+    }
+}
+
+impl<T> List<T> {
+    pub fn push_front_handle(&mut self, elem: T) -> NodeHandle<T> {
+        let node = Node::new(elem);
+        let handle = self.handle(&node);
+        self.link_front(node);
+        handle
+    }
+
+    pub fn push_back_handle(&mut self, elem: T) -> NodeHandle<T> {
+        let node = Node::new(elem);
+        let handle = self.handle(&node);
+        self.link_back(node);
+        handle
+    }
+
+    fn handle(&self, node: &Rc<RefCell<Node<T>>>) -> NodeHandle<T> {
+        NodeHandle {
+            node: Rc::downgrade(node),
+            list: self.id,
+        }
+    }
+
+    // The node behind `handle`, if it's still in this list.
+    fn upgrade(&self, handle: &NodeHandle<T>) -> Option<Rc<RefCell<Node<T>>>> {
+        assert_eq!(
+            handle.list, self.id,
+            "node handle used with a list it doesn't belong to"
+        );
+        handle.node.upgrade()
+    }
+
+    // The list holds the only strong references to its nodes, so a node we
+    // can upgrade to is still linked in, and stays that way for as long as
+    // the list is borrowed; the same reasoning as the borrowing iterators.
+    pub fn get(&self, handle: &NodeHandle<T>) -> Option<Ref<'_, T>> {
+        let node = self.upgrade(handle)?;
+        let node = unsafe { &*Rc::as_ptr(&node) };
+        Some(Ref::map(node.borrow(), |node| &node.elem))
+    }
+
+    pub fn get_mut(&mut self, handle: &NodeHandle<T>) -> Option<RefMut<'_, T>> {
+        let node = self.upgrade(handle)?;
+        let node = unsafe { &*Rc::as_ptr(&node) };
+        Some(RefMut::map(node.borrow_mut(), |node| &mut node.elem))
+    }
+
+    // Takes the node out of the chain, leaving it unlinked but alive for as
+    // long as the caller holds on to it.
+    fn unlink(&mut self, node: &Rc<RefCell<Node<T>>>) {
+        let mut node = node.borrow_mut();
+        let prev = node.prev.take().and_then(|prev| prev.upgrade());
+        let next = node.next.take();
+        match &prev {
+            Some(prev) => prev.borrow_mut().next = next.clone(),
+            None => self.head = next.clone(),
+        }
+        match &next {
+            Some(next) => next.borrow_mut().prev = prev.as_ref().map(Rc::downgrade),
+            None => self.tail = prev,
+        }
+        self.len -= 1;
+    }
+
+    // Removes the node wherever it is, in O(1).
+    pub fn remove(&mut self, handle: &NodeHandle<T>) -> Option<T> {
+        let node = self.upgrade(handle)?;
+        self.unlink(&node);
+        Some(Rc::try_unwrap(node).ok().unwrap().into_inner().elem)
+    }
+
+    // Returns false if the handle is stale.
+    pub fn move_to_front(&mut self, handle: &NodeHandle<T>) -> bool {
+        match self.upgrade(handle) {
+            Some(node) => {
+                self.unlink(&node);
+                self.link_front(node);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Gives `elem` back if the handle is stale.
+    pub fn insert_after(&mut self, handle: &NodeHandle<T>, elem: T) -> Result<NodeHandle<T>, T> {
+        let node = match self.upgrade(handle) {
+            Some(node) => node,
+            None => return Err(elem),
+        };
+        let new_node = Node::new(elem);
+        let new_handle = self.handle(&new_node);
+        match node.borrow_mut().next.take() {
+            Some(next) => {
+                next.borrow_mut().prev = Some(Rc::downgrade(&new_node));
+                new_node.borrow_mut().next = Some(next);
+            }
+            None => self.tail = Some(new_node.clone()),
+        }
+        new_node.borrow_mut().prev = Some(Rc::downgrade(&node));
+        node.borrow_mut().next = Some(new_node);
+        self.len += 1;
+        Ok(new_handle)
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Collection<T> for List<T> {
+    type Ref<'a>
+        = Ref<'a, T>
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Stack<T> for List<T> {
+    fn push(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn peek(&self) -> Option<Ref<'_, T>> {
+        self.peek_front()
+    }
+}
+
+impl<T> Queue<T> for List<T> {
+    fn enqueue(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn front(&self) -> Option<Ref<'_, T>> {
+        self.peek_front()
+    }
+}
+
+impl<T> Deque<T> for List<T> {
+    fn push_front(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+
+    fn peek_front(&self) -> Option<Ref<'_, T>> {
+        self.peek_front()
+    }
+
+    fn peek_back(&self) -> Option<Ref<'_, T>> {
+        self.peek_back()
+    }
+}
+
+// Not needed to break cycles any more (there aren't any), but dropping a long
+// chain of `next` links recursively would overflow the stack.
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
         while self.pop_front().is_some() {}
@@ -140,8 +383,11 @@ impl<T> Drop for List<T> {
 
 pub struct IntoIter<T>(List<T>);
 
-impl<T> List<T> {
-    pub fn into_iter(self) -> IntoIter<T> {
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
         IntoIter(self)
     }
 }
@@ -151,17 +397,251 @@ impl<T> Iterator for IntoIter<T> {
     fn next(&mut self) -> Option<T> {
         self.0.pop_front()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
 }
 
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
 impl<T> DoubleEndedIterator for IntoIter<T> {
     fn next_back(&mut self) -> Option<T> {
         self.0.pop_back()
     }
 }
 
+// Borrowing iterators hand out `Ref`/`RefMut` guards, one node at a time.
+//
+// A guard can only borrow from a `&'a RefCell`, but the next node is only
+// reachable through the current node's `RefCell`, so its reference is tied to
+// that borrow rather than to `'a`. It's fine to extend it: links only change
+// through `&mut List`, and the iterator holds the list borrowed for `'a`, so
+// every node stays alive and linked where it is until the iterator is gone.
+// `len` keeps the two ends from handing out the same node twice.
+pub struct Iter<'a, T> {
+    head: Option<&'a RefCell<Node<T>>>,
+    tail: Option<&'a RefCell<Node<T>>>,
+    len: usize,
+}
+
+pub struct IterMut<'a, T> {
+    head: Option<&'a RefCell<Node<T>>>,
+    tail: Option<&'a RefCell<Node<T>>>,
+    len: usize,
+}
+
+// See above for why the lifetime can be stretched to `'a`.
+fn extend<'a, T>(link: &Link<T>) -> Option<&'a RefCell<Node<T>>> {
+    link.as_ref().map(|node| unsafe { &*Rc::as_ptr(node) })
+}
+
+// The same, going backwards. A weak link's node is alive as long as it's in
+// the list, so its pointer is as good as a strong one.
+fn extend_weak<'a, T>(link: &WeakLink<T>) -> Option<&'a RefCell<Node<T>>> {
+    link.as_ref().map(|node| unsafe { &*node.as_ptr() })
+}
+
+impl<T> List<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head.as_deref(),
+            tail: self.tail.as_deref(),
+            len: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.head.as_deref(),
+            tail: self.tail.as_deref(),
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = Ref<'a, T>;
+
+    fn next(&mut self) -> Option<Ref<'a, T>> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| {
+            let node = node.borrow();
+            self.head = extend(&node.next);
+            self.len -= 1;
+            Ref::map(node, |node| &node.elem)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Ref<'a, T>> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| {
+            let node = node.borrow();
+            self.tail = extend_weak(&node.prev);
+            self.len -= 1;
+            Ref::map(node, |node| &node.elem)
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = RefMut<'a, T>;
+
+    fn next(&mut self) -> Option<RefMut<'a, T>> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| {
+            let node = node.borrow_mut();
+            self.head = extend(&node.next);
+            self.len -= 1;
+            RefMut::map(node, |node| &mut node.elem)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<RefMut<'a, T>> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| {
+            let node = node.borrow_mut();
+            self.tail = extend_weak(&node.prev);
+            self.len -= 1;
+            RefMut::map(node, |node| &mut node.elem)
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = Ref<'a, T>;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut List<T> {
+    type Item = RefMut<'a, T>;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+// Lexicographic comparison driven by `f`; a list that runs out first is less.
+fn compare<T, F>(a: &List<T>, b: &List<T>, mut f: F) -> Option<Ordering>
+where
+    F: FnMut(&T, &T) -> Option<Ordering>,
+{
+    let mut a = a.iter();
+    let mut b = b.iter();
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return Some(Ordering::Equal),
+            (None, Some(_)) => return Some(Ordering::Less),
+            (Some(_), None) => return Some(Ordering::Greater),
+            (Some(a), Some(b)) => match f(&a, &b) {
+                Some(Ordering::Equal) => {}
+                ordering => return ordering,
+            },
+        }
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        self.iter().map(|elem| elem.clone()).collect()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        let eq = compare(self, other, |a, b| {
+            if a == b {
+                Some(Ordering::Equal)
+            } else {
+                None
+            }
+        });
+        eq == Some(Ordering::Equal)
+    }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        compare(self, other, T::partial_cmp)
+    }
+}
+
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare(self, other, |a, b| Some(a.cmp(b))).unwrap()
+    }
+}
+
+impl<T: Hash> Hash for List<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elem in self {
+            elem.hash(state);
+        }
+    }
+}
+
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        list.extend(iter);
+        list
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::List;
+    use crate::test_util::{hash, Drops};
+    use std::mem;
+
+    crate::deque_conformance!(conformance, crate::rc_deque::List<i32>);
+
     #[test]
     fn basics() {
         let mut list = List::new();
@@ -249,4 +729,219 @@ mod test {
         assert_eq!(iter.next_back(), None);
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn iter() {
+        let list: List<i32> = (1..=5).collect();
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(*iter.next().unwrap(), 1);
+        assert_eq!(*iter.next_back().unwrap(), 5);
+        assert_eq!(*iter.next_back().unwrap(), 4);
+        // guards from both ends can be held at once, even on one node
+        let two = iter.next().unwrap();
+        let also_two = list.iter().nth(1).unwrap();
+        let three = iter.next_back().unwrap();
+        assert_eq!((*two, *also_two, *three), (2, 2, 3));
+        // the ends have met; neither may walk past the other
+        assert!(iter.next().is_none());
+        assert!(iter.next_back().is_none());
+
+        let elems: Vec<i32> = list.iter().rev().map(|elem| *elem).collect();
+        assert_eq!(elems, vec![5, 4, 3, 2, 1]);
+        // iterating didn't consume anything
+        assert_eq!(list.len(), 5);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut list: List<i32> = (1..=4).collect();
+
+        let mut iter = list.iter_mut();
+        let mut front = iter.next().unwrap();
+        let mut back = iter.next_back().unwrap();
+        *front *= 10;
+        *back *= 10;
+        drop((front, back));
+        assert_eq!(iter.len(), 2);
+
+        for mut elem in &mut list {
+            *elem += 1;
+        }
+        let elems: Vec<i32> = (&list).into_iter().map(|elem| *elem).collect();
+        assert_eq!(elems, vec![11, 3, 4, 41]);
+
+        for mut elem in list.iter_mut().rev().take(1) {
+            *elem = 0;
+        }
+        assert_eq!(list.pop_back(), Some(0));
+        // a finished `iter_mut` left every node unborrowed
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![11, 3, 4]);
+    }
+
+    #[test]
+    fn handles() {
+        let mut list = List::new();
+        let one = list.push_back_handle(1);
+        let two = list.push_back_handle(2);
+        let zero = list.push_front_handle(0);
+        assert_eq!(*list.get(&one).unwrap(), 1);
+        *list.get_mut(&two).unwrap() = 20;
+
+        // insert in the middle and at the tail
+        let half = list.insert_after(&zero, 5).ok().unwrap();
+        let last = list.insert_after(&two, 30).ok().unwrap();
+        assert_eq!(list.peek_back().as_deref(), Some(&30));
+        assert_eq!(list.len(), 5);
+        let elems: Vec<_> = list.iter().map(|elem| *elem).collect();
+        assert_eq!(elems, vec![0, 5, 1, 20, 30]);
+
+        // move from the middle, the back, and the front itself
+        assert!(list.move_to_front(&one));
+        assert!(list.move_to_front(&last));
+        assert!(list.move_to_front(&last));
+        let elems: Vec<_> = list.iter().map(|elem| *elem).collect();
+        assert_eq!(elems, vec![30, 1, 0, 5, 20]);
+        assert_eq!(list.peek_back().as_deref(), Some(&20));
+
+        // remove from the middle, the back and the front
+        assert_eq!(list.remove(&zero), Some(0));
+        assert_eq!(list.remove(&two), Some(20));
+        assert_eq!(list.remove(&last), Some(30));
+        assert_eq!(list.len(), 2);
+        let elems: Vec<_> = list.iter().map(|elem| *elem).collect();
+        assert_eq!(elems, vec![1, 5]);
+        let back: Vec<_> = list.iter().rev().map(|elem| *elem).collect();
+        assert_eq!(back, vec![5, 1]);
+
+        // removed nodes leave stale handles behind
+        assert!(!zero.is_live());
+        assert_eq!(list.remove(&zero), None);
+        assert!(!list.move_to_front(&two));
+        assert_eq!(list.insert_after(&last, 7).err(), Some(7));
+        assert!(list.get(&two).is_none());
+
+        // and so do popped ones
+        assert!(half.clone().is_live());
+        assert_eq!(list.pop_back(), Some(5));
+        assert!(!half.is_live());
+        assert_eq!(list.remove(&one), Some(1));
+        assert!(list.is_empty());
+        assert!(list.peek_front().is_none());
+        assert!(list.peek_back().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "node handle used with a list it doesn't belong to")]
+    fn handle_from_another_list() {
+        let mut list = List::new();
+        let mut other = List::new();
+        let handle = other.push_back_handle(1);
+        list.push_back(1);
+        list.remove(&handle);
+    }
+
+    #[test]
+    fn no_leaks() {
+        let drops = Drops::new();
+        let mut list = List::new();
+        for _ in 0..5 {
+            list.push_front(drops.track());
+            list.push_back(drops.track());
+        }
+        assert_eq!(drops.live(), 10);
+        drop(list.pop_front());
+        drop(list.pop_back());
+        assert_eq!(drops.live(), 8);
+
+        // handles never keep anything alive, even once the list is gone
+        let front = list.push_front_handle(drops.track());
+        let back = list.push_back_handle(drops.track());
+        let middle = list.insert_after(&front, drops.track()).ok().unwrap();
+        list.move_to_front(&back);
+        drop(list.remove(&middle));
+        assert_eq!(drops.live(), 10);
+        drop(list);
+        assert_eq!(drops.live(), 0);
+        assert!(!front.is_live() && !back.is_live());
+
+        // nor do half-used iterators
+        let mut list: List<_> = (0..4).map(|_| drops.track()).collect();
+        list.iter_mut().next_back();
+        let mut iter = list.into_iter();
+        iter.next();
+        iter.next_back();
+        drop(iter);
+        assert_eq!(drops.live(), 0);
+    }
+
+    // The nodes must not keep each other alive: dropping the chain from the
+    // head frees everything even if `List::drop` never runs.
+    #[test]
+    fn chain_has_no_cycles() {
+        let drops = Drops::new();
+        let mut list = List::new();
+        for _ in 0..3 {
+            list.push_back(drops.track());
+        }
+        let handle = list.push_front_handle(drops.track());
+        list.insert_after(&handle, drops.track()).ok().unwrap();
+        assert_eq!(drops.live(), 5);
+
+        let head = list.head.take();
+        list.tail.take();
+        mem::forget(list);
+        drop(head);
+        assert_eq!(drops.live(), 0);
+    }
+
+    #[test]
+    fn std_traits() {
+        let list: List<i32> = vec![1, 2, 3].into_iter().collect();
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+
+        let mut copy = list.clone();
+        assert_eq!(copy, list);
+        assert_eq!(hash(&copy), hash(&list));
+
+        // Check the clone doesn't share nodes with the original
+        *copy.peek_front_mut().unwrap() = 0;
+        assert_eq!(&*list.peek_front().unwrap(), &1);
+        assert!(copy < list);
+        copy.pop_front();
+        copy.push_front(1);
+        copy.extend(vec![4]);
+        assert_eq!(format!("{:?}", copy), "[1, 2, 3, 4]");
+        assert!(copy > list);
+        assert_eq!(copy.partial_cmp(&list), Some(std::cmp::Ordering::Greater));
+        assert_eq!(List::<i32>::default(), List::new());
+    }
+
+    #[test]
+    fn len() {
+        let mut list = List::new();
+        assert_eq!(list.len(), 0);
+        assert!(list.is_empty());
+
+        list.push_front(2);
+        list.push_back(3);
+        list.push_front(1);
+        assert_eq!(list.len(), 3);
+        list.pop_back();
+        assert_eq!(list.len(), 2);
+        list.pop_front();
+        list.pop_front();
+        list.pop_front();
+        assert_eq!(list.len(), 0);
+
+        list.extend(vec![1, 2, 3, 4]);
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 4);
+        iter.next();
+        iter.next_back();
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        let rest: Vec<_> = iter.collect();
+        assert_eq!(rest, vec![2, 3]);
+    }
 }
@@ -0,0 +1,266 @@
+// Hazard pointers: just enough safe memory reclamation for the lock-free
+// lists.
+//
+// A thread that is about to dereference a shared node first publishes the
+// node's address in a hazard record, then checks the node is still reachable.
+// A thread that unlinks a node doesn't free it; it *retires* it, and retired
+// nodes are only freed once no hazard record holds their address.
+//
+// Every list owns its own `Domain`. Hazards borrow the domain, so by the time
+// the domain (and the list) is dropped nobody can be looking at anything, and
+// whatever is still retired can be freed without checking.
+
+use std::ptr;
+use std::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering::*};
+
+// How many retired nodes to let pile up before scanning the hazards.
+const RECLAIM_THRESHOLD: usize = 64;
+
+pub(crate) struct Domain {
+    // Only ever grows; records are reused, not removed, until the domain dies.
+    records: AtomicPtr<Record>,
+    retired: AtomicPtr<Retired>,
+    retired_count: AtomicUsize,
+}
+
+struct Record {
+    ptr: AtomicPtr<()>,
+    active: AtomicBool,
+    next: *mut Record,
+}
+
+struct Retired {
+    ptr: *mut (),
+    free: unsafe fn(*mut ()),
+    next: *mut Retired,
+}
+
+pub(crate) struct Hazard<'a> {
+    record: &'a Record,
+}
+
+unsafe fn free_box<T>(ptr: *mut ()) {
+    drop(Box::from_raw(ptr as *mut T));
+}
+
+impl Domain {
+    pub fn new() -> Self {
+        Domain {
+            records: AtomicPtr::new(ptr::null_mut()),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            retired_count: AtomicUsize::new(0),
+        }
+    }
+
+    // Claims an idle record, or adds a new one if every record is in use.
+    pub fn hazard(&self) -> Hazard<'_> {
+        let mut cur = self.records.load(Acquire);
+        while !cur.is_null() {
+            let record = unsafe { &*cur };
+            if record
+                .active
+                .compare_exchange(false, true, Acquire, Relaxed)
+                .is_ok()
+            {
+                return Hazard { record };
+            }
+            cur = record.next;
+        }
+
+        let new = Box::into_raw(Box::new(Record {
+            ptr: AtomicPtr::new(ptr::null_mut()),
+            active: AtomicBool::new(true),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.records.load(Acquire);
+        loop {
+            unsafe { (*new).next = head };
+            match self
+                .records
+                .compare_exchange_weak(head, new, AcqRel, Acquire)
+            {
+                Ok(_) => {
+                    return Hazard {
+                        record: unsafe { &*new },
+                    }
+                }
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    // Whether any hazard holds `ptr`.
+    fn is_protected(&self, ptr: *mut ()) -> bool {
+        let mut cur = self.records.load(Acquire);
+        while !cur.is_null() {
+            let record = unsafe { &*cur };
+            if record.ptr.load(SeqCst) == ptr {
+                return true;
+            }
+            cur = record.next;
+        }
+        false
+    }
+
+    // Hands a `Box`-allocated pointer over to be freed once no hazard holds
+    // it. The caller must have made it unreachable for anyone who hasn't
+    // already protected it.
+    pub unsafe fn retire<T>(&self, ptr: *mut T) {
+        let retired = Box::into_raw(Box::new(Retired {
+            ptr: ptr as *mut (),
+            free: free_box::<T>,
+            next: ptr::null_mut(),
+        }));
+        // Count it before publishing it: once it's on the list, a concurrent
+        // `reclaim` may free it and subtract it from the count straight away.
+        let count = self.retired_count.fetch_add(1, Relaxed) + 1;
+        self.push_retired(retired, retired);
+        if count >= RECLAIM_THRESHOLD {
+            self.reclaim();
+        }
+    }
+
+    // Pushes the chain `first..=last` onto the retired list.
+    fn push_retired(&self, first: *mut Retired, last: *mut Retired) {
+        let mut head = self.retired.load(Acquire);
+        loop {
+            unsafe { (*last).next = head };
+            match self
+                .retired
+                .compare_exchange_weak(head, first, AcqRel, Acquire)
+            {
+                Ok(_) => return,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    // Takes the whole retired list, frees what nobody is protecting, and puts
+    // the rest back.
+    fn reclaim(&self) {
+        // `protect` is a store to a hazard followed by a load of the source;
+        // an unlink is a store to the source followed by this scan of the
+        // hazards. For one side to be sure to see the other, both have to be
+        // in the single SeqCst order, and the lists unlink with AcqRel CASes,
+        // so the fence puts this side there.
+        fence(SeqCst);
+        let mut cur = self.retired.swap(ptr::null_mut(), AcqRel);
+        let mut kept: *mut Retired = ptr::null_mut();
+        let mut kept_last: *mut Retired = ptr::null_mut();
+        let mut freed = 0;
+        while !cur.is_null() {
+            let retired = cur;
+            unsafe {
+                cur = (*retired).next;
+                if self.is_protected((*retired).ptr) {
+                    (*retired).next = kept;
+                    if kept.is_null() {
+                        kept_last = retired;
+                    }
+                    kept = retired;
+                } else {
+                    ((*retired).free)((*retired).ptr);
+                    drop(Box::from_raw(retired));
+                    freed += 1;
+                }
+            }
+        }
+        self.retired_count.fetch_sub(freed, Relaxed);
+        if !kept.is_null() {
+            self.push_retired(kept, kept_last);
+        }
+    }
+}
+
+impl Drop for Domain {
+    fn drop(&mut self) {
+        let mut cur = *self.retired.get_mut();
+        while !cur.is_null() {
+            unsafe {
+                let retired = Box::from_raw(cur);
+                (retired.free)(retired.ptr);
+                cur = retired.next;
+            }
+        }
+
+        let mut cur = *self.records.get_mut();
+        while !cur.is_null() {
+            let record = unsafe { Box::from_raw(cur) };
+            cur = record.next;
+        }
+    }
+}
+
+impl Hazard<'_> {
+    // Loads `src` and keeps the result protected, retrying until the value
+    // we've published is still the one in `src`. Once this returns, the
+    // pointer stays valid until the hazard is reset or dropped.
+    pub fn protect<T>(&self, src: &AtomicPtr<T>) -> *mut T {
+        let mut ptr = src.load(SeqCst);
+        loop {
+            self.record.ptr.store(ptr as *mut (), SeqCst);
+            let again = src.load(SeqCst);
+            if again == ptr {
+                return ptr;
+            }
+            ptr = again;
+        }
+    }
+
+    pub fn reset(&self) {
+        self.record.ptr.store(ptr::null_mut(), Release);
+    }
+}
+
+impl Drop for Hazard<'_> {
+    fn drop(&mut self) {
+        self.reset();
+        self.record.active.store(false, Release);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Domain, RECLAIM_THRESHOLD};
+    use crate::test_util::Drops;
+    use std::sync::atomic::AtomicPtr;
+
+    #[test]
+    fn reuses_records() {
+        let domain = Domain::new();
+        let a = domain.hazard();
+        let b = domain.hazard();
+        assert!(!std::ptr::eq(a.record, b.record));
+        let a_record = a.record as *const _;
+        drop(a);
+        let c = domain.hazard();
+        assert_eq!(c.record as *const _, a_record);
+    }
+
+    #[test]
+    fn protected_pointers_survive_reclaim() {
+        let drops = Drops::new();
+        let domain = Domain::new();
+
+        let kept = Box::into_raw(Box::new(drops.track()));
+        let src = AtomicPtr::new(kept);
+        let hazard = domain.hazard();
+        assert_eq!(hazard.protect(&src), kept);
+        assert!(domain.is_protected(kept as *mut ()));
+
+        unsafe {
+            domain.retire(kept);
+            for _ in 1..RECLAIM_THRESHOLD {
+                domain.retire(Box::into_raw(Box::new(drops.track())));
+            }
+        }
+        // hitting the threshold freed everything but the protected one
+        assert_eq!(drops.dropped(), RECLAIM_THRESHOLD - 1);
+        assert_eq!(drops.live(), 1);
+
+        drop(hazard);
+        assert!(!domain.is_protected(kept as *mut ()));
+        drop(domain);
+        assert_eq!(drops.dropped(), RECLAIM_THRESHOLD);
+    }
+}
@@ -1,5 +1,150 @@
+//! A handful of linked lists, each exploring a different ownership model.
+//!
+//! Every implementation lives behind its own cargo feature (all enabled by
+//! default) so downstream crates only compile the lists they use:
+//!
+//! | module               | feature            | what it is                                  |
+//! |----------------------|--------------------|---------------------------------------------|
+//! | [`int_stack`]        | `int_stack`        | `i32`-only singly-linked stack              |
+//! | [`stack`]            | `stack`            | generic singly-linked stack                 |
+//! | [`persistent`]       | `persistent`       | immutable, `Rc`-shared singly-linked list   |
+//! | [`rc_deque`]         | `rc_deque`         | `Rc<RefCell<_>>` doubly-linked deque        |
+//! | [`queue`]            | `queue`            | singly-linked queue with a raw tail pointer |
+//! | [`concurrent_stack`] | `concurrent_stack` | lock-free Treiber stack                     |
+//! | [`concurrent_queue`] | `concurrent_queue` | lock-free Michael-Scott queue               |
+//! | [`blocking_queue`]   | `blocking_queue`   | bounded `queue` behind a `Mutex`/`Condvar`  |
+//! | [`async_queue`]      | `async_queue`      | `queue` with a `Future`-based `recv`        |
+//! | [`linked_deque`]     | `linked_deque`     | raw-pointer doubly-linked deque             |
+//! | [`lru_cache`]        | `lru_cache`        | LRU cache over `rc_deque` and a `HashMap`   |
+//! | [`arc_persistent`]   | `arc_persistent`   | `persistent` with `Arc`, for sharing        |
+//! | [`persistent_queue`] | `persistent_queue` | real-time queue made of `persistent`s       |
+
+pub mod conformance;
+pub mod traits;
+
+#[cfg(any(feature = "concurrent_stack", feature = "concurrent_queue"))]
+mod hazard;
+#[cfg(test)]
+mod model;
+#[cfg(test)]
+mod test_util;
+
+pub use crate::traits::{Collection, Deque, Queue, Stack};
+
+#[cfg(feature = "queue")]
 mod fifth;
+#[cfg(feature = "int_stack")]
 mod first;
+#[cfg(feature = "rc_deque")]
 mod fourth;
+#[cfg(feature = "concurrent_queue")]
+mod ms_queue;
+#[cfg(feature = "stack")]
 mod second;
+#[cfg(feature = "persistent")]
 mod third;
+#[cfg(feature = "concurrent_stack")]
+mod treiber_stack;
+
+/// A singly-linked stack of `i32`s.
+#[cfg(feature = "int_stack")]
+pub mod int_stack {
+    pub use crate::first::*;
+}
+
+/// A generic singly-linked stack.
+#[cfg(feature = "stack")]
+pub mod stack {
+    pub use crate::second::*;
+}
+
+/// A persistent singly-linked list whose tails are shared through `Rc`.
+#[cfg(feature = "persistent")]
+pub mod persistent {
+    pub use crate::third::*;
+}
+
+/// A doubly-linked deque built from `Rc<RefCell<_>>` nodes.
+#[cfg(feature = "rc_deque")]
+pub mod rc_deque {
+    pub use crate::fourth::*;
+}
+
+/// A singly-linked queue that tracks its tail with a raw pointer.
+#[cfg(feature = "queue")]
+pub mod queue {
+    pub use crate::fifth::*;
+}
+
+/// A lock-free stack that can be shared between threads.
+#[cfg(feature = "concurrent_stack")]
+pub mod concurrent_stack {
+    pub use crate::treiber_stack::*;
+}
+
+/// A lock-free queue that can be shared between threads.
+#[cfg(feature = "concurrent_queue")]
+pub mod concurrent_queue {
+    pub use crate::ms_queue::*;
+}
+
+/// A bounded queue whose `push` and `pop` block, for producer/consumer
+/// handoff between threads.
+#[cfg(feature = "blocking_queue")]
+pub mod blocking_queue;
+
+/// An unbounded queue whose consumers wait for elements with `.await`.
+#[cfg(feature = "async_queue")]
+pub mod async_queue;
+
+/// A doubly-linked deque that owns its nodes through raw pointers, with
+/// borrowing iterators that run from either end.
+#[cfg(feature = "linked_deque")]
+pub mod linked_deque;
+
+/// A least-recently-used cache that keeps its recency order in an
+/// `rc_deque`.
+#[cfg(feature = "lru_cache")]
+pub mod lru_cache;
+
+/// The persistent list again, with `Arc`-shared tails so versions can be
+/// sent to and read from other threads.
+#[cfg(feature = "arc_persistent")]
+pub mod arc_persistent;
+
+/// An immutable FIFO queue built from persistent lists, where every
+/// operation returns a new version that shares nodes with the old one.
+#[cfg(feature = "persistent_queue")]
+pub mod persistent_queue;
+
+/// The list types under distinct names, plus the traits, for glob importing.
+pub mod prelude {
+    pub use crate::traits::{Collection, Deque, Queue, Stack};
+
+    #[cfg(feature = "arc_persistent")]
+    pub use crate::arc_persistent::List as ArcPersistentList;
+    #[cfg(feature = "async_queue")]
+    pub use crate::async_queue::List as AsyncQueue;
+    #[cfg(feature = "blocking_queue")]
+    pub use crate::blocking_queue::List as BlockingQueue;
+    #[cfg(feature = "concurrent_queue")]
+    pub use crate::concurrent_queue::List as ConcurrentQueue;
+    #[cfg(feature = "concurrent_stack")]
+    pub use crate::concurrent_stack::List as ConcurrentStack;
+    #[cfg(feature = "int_stack")]
+    pub use crate::int_stack::List as IntStack;
+    #[cfg(feature = "linked_deque")]
+    pub use crate::linked_deque::List as LinkedDeque;
+    #[cfg(feature = "lru_cache")]
+    pub use crate::lru_cache::LruCache;
+    #[cfg(feature = "persistent")]
+    pub use crate::persistent::List as PersistentList;
+    #[cfg(feature = "persistent_queue")]
+    pub use crate::persistent_queue::List as PersistentQueue;
+    #[cfg(feature = "queue")]
+    pub use crate::queue::List as LinkedQueue;
+    #[cfg(feature = "rc_deque")]
+    pub use crate::rc_deque::List as RcDeque;
+    #[cfg(feature = "stack")]
+    pub use crate::stack::List as LinkedStack;
+}
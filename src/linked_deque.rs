@@ -0,0 +1,731 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::NonNull;
+
+use crate::traits::{Collection, Deque, Queue, Stack};
+
+// `fourth`'s deque without the `Rc<RefCell<_>>`: every node is owned by the
+// list through raw pointers, so both ends are plain `&`/`&mut` access, and
+// iterators can hand out real references from either end.
+//
+// `NonNull` (rather than `*mut`) keeps the list covariant in `T`, the same as
+// `Box<T>` or `Vec<T>`, and the `PhantomData<T>` tells the drop checker that
+// dropping a `List<T>` drops `T`s.
+pub struct List<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    _boo: PhantomData<T>,
+}
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    elem: T,
+    next: Link<T>,
+    prev: Link<T>,
+}
+
+// The raw pointers opt us out of Send and Sync, but they only ever point into
+// nodes the list owns, so it's as thread-safe as a `Vec<T>`.
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Sync> Sync for List<T> {}
+
+pub struct IntoIter<T>(List<T>);
+
+// Walks inwards from both ends; `len` is what stops the two ends from
+// crossing over once they meet in the middle.
+pub struct Iter<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a T>,
+}
+
+pub struct IterMut<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a mut T>,
+}
+
+// Same as `&T` and `&mut T`.
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        List {
+            head: None,
+            tail: None,
+            len: 0,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        let new_head = NonNull::from(Box::leak(Box::new(Node {
+            elem,
+            next: self.head,
+            prev: None,
+        })));
+        match self.head {
+            Some(old_head) => unsafe { (*old_head.as_ptr()).prev = Some(new_head) },
+            None => self.tail = Some(new_head),
+        }
+        self.head = Some(new_head);
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        let new_tail = NonNull::from(Box::leak(Box::new(Node {
+            elem,
+            next: None,
+            prev: self.tail,
+        })));
+        match self.tail {
+            Some(old_tail) => unsafe { (*old_tail.as_ptr()).next = Some(new_tail) },
+            None => self.head = Some(new_tail),
+        }
+        self.tail = Some(new_tail);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|old_head| unsafe {
+            let old_head = Box::from_raw(old_head.as_ptr());
+            self.head = old_head.next;
+            match self.head {
+                Some(new_head) => (*new_head.as_ptr()).prev = None,
+                None => self.tail = None,
+            }
+            self.len -= 1;
+            old_head.elem
+        })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.map(|old_tail| unsafe {
+            let old_tail = Box::from_raw(old_tail.as_ptr());
+            self.tail = old_tail.prev;
+            match self.tail {
+                Some(new_tail) => (*new_tail.as_ptr()).next = None,
+                None => self.head = None,
+            }
+            self.len -= 1;
+            old_tail.elem
+        })
+    }
+
+    pub fn peek_front(&self) -> Option<&T> {
+        self.head.map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+
+    pub fn peek_front_mut(&mut self) -> Option<&mut T> {
+        self.head.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    pub fn peek_back(&self) -> Option<&T> {
+        self.tail.map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+
+    pub fn peek_back_mut(&mut self) -> Option<&mut T> {
+        self.tail.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    // Moves all of `other`'s elements onto our back, leaving it empty. O(1);
+    // only the nodes at the seam are touched.
+    pub fn append(&mut self, other: &mut List<T>) {
+        let Some(other_head) = other.head.take() else {
+            return;
+        };
+        match self.tail {
+            Some(tail) => unsafe {
+                (*tail.as_ptr()).next = Some(other_head);
+                (*other_head.as_ptr()).prev = Some(tail);
+            },
+            None => self.head = Some(other_head),
+        }
+        self.tail = other.tail.take();
+        self.len += mem::replace(&mut other.len, 0);
+    }
+
+    // Splits off everything from index `at` back, keeping the first `at`
+    // elements. Panics if `at > len`. O(min(at, len - at)): the cut is found
+    // from whichever end is nearer.
+    pub fn split_off(&mut self, at: usize) -> List<T> {
+        assert!(at <= self.len, "split_off index out of bounds");
+        if at == 0 {
+            return mem::take(self);
+        }
+        if at == self.len {
+            return List::new();
+        }
+        unsafe {
+            // The first node of the back half. Both halves are non-empty, so
+            // every link followed here exists.
+            let first = if at <= self.len / 2 {
+                let mut node = self.head.unwrap();
+                for _ in 0..at {
+                    node = (*node.as_ptr()).next.unwrap();
+                }
+                node
+            } else {
+                let mut node = self.tail.unwrap();
+                for _ in at + 1..self.len {
+                    node = (*node.as_ptr()).prev.unwrap();
+                }
+                node
+            };
+            let last = (*first.as_ptr()).prev.take().unwrap();
+            (*last.as_ptr()).next = None;
+
+            let rest = List {
+                head: Some(first),
+                tail: self.tail,
+                len: self.len - at,
+                _boo: PhantomData,
+            };
+            self.tail = Some(last);
+            self.len = at;
+            rest
+        }
+    }
+
+    pub fn contains(&self, elem: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|e| e == elem)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Collection<T> for List<T> {
+    type Ref<'a>
+        = &'a T
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Stack<T> for List<T> {
+    fn push(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek_front()
+    }
+}
+
+impl<T> Queue<T> for List<T> {
+    fn enqueue(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn front(&self) -> Option<&T> {
+        self.peek_front()
+    }
+}
+
+impl<T> Deque<T> for List<T> {
+    fn push_front(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+
+    fn peek_front(&self) -> Option<&T> {
+        self.peek_front()
+    }
+
+    fn peek_back(&self) -> Option<&T> {
+        self.peek_back()
+    }
+}
+
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            self.len -= 1;
+            self.head = (*node.as_ptr()).next;
+            &(*node.as_ptr()).elem
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            self.len -= 1;
+            self.tail = (*node.as_ptr()).prev;
+            &(*node.as_ptr()).elem
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            self.len -= 1;
+            self.head = (*node.as_ptr()).next;
+            &mut (*node.as_ptr()).elem
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            self.len -= 1;
+            self.tail = (*node.as_ptr()).prev;
+            &mut (*node.as_ptr()).elem
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut List<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for List<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elem in self {
+            elem.hash(state);
+        }
+    }
+}
+
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        list.extend(iter);
+        list
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{IntoIter, Iter, List};
+    use crate::test_util::hash;
+
+    crate::deque_conformance!(conformance, crate::linked_deque::List<i32>);
+
+    #[test]
+    fn basics() {
+        let mut list = List::new();
+
+        // Check empty list behaves right
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+
+        // Populate list
+        list.push_front(1);
+        list.push_front(2);
+        list.push_back(3);
+
+        // Check normal removal
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(list.pop_back(), Some(3));
+
+        // Push some more just to make sure nothing's corrupted
+        list.push_back(4);
+        list.push_front(5);
+
+        // Check normal removal
+        assert_eq!(list.pop_back(), Some(4));
+        assert_eq!(list.pop_front(), Some(5));
+
+        // Check exhaustion from either end
+        assert_eq!(list.pop_back(), Some(1));
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+
+        // Check the exhaustion case fixed both pointers right
+        list.push_back(6);
+        list.push_back(7);
+        assert_eq!(list.pop_front(), Some(6));
+        assert_eq!(list.pop_front(), Some(7));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn peek() {
+        let mut list = List::new();
+        assert!(list.peek_front().is_none());
+        assert!(list.peek_back().is_none());
+        assert!(list.peek_front_mut().is_none());
+        assert!(list.peek_back_mut().is_none());
+
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+        assert_eq!(list.peek_front(), Some(&1));
+        assert_eq!(list.peek_back(), Some(&3));
+
+        *list.peek_front_mut().unwrap() = 10;
+        *list.peek_back_mut().unwrap() = 30;
+        assert_eq!(list.pop_front(), Some(10));
+        assert_eq!(list.pop_back(), Some(30));
+        assert_eq!(list.peek_front(), list.peek_back());
+    }
+
+    #[test]
+    fn iter() {
+        let mut list: List<i32> = (1..=5).collect();
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&5));
+        assert_eq!(iter.next_back(), Some(&4));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.clone().collect::<Vec<_>>(), vec![&3]);
+        assert_eq!(iter.next_back(), Some(&3));
+        // the ends have met; neither may walk past the other
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        for elem in list.iter_mut().rev().take(2) {
+            *elem *= 10;
+        }
+        let mut iter = list.iter_mut();
+        assert_eq!(iter.next(), Some(&mut 1));
+        assert_eq!(iter.next_back(), Some(&mut 50));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back(), Some(&mut 40));
+        assert_eq!(iter.next(), Some(&mut 2));
+        assert_eq!(iter.next(), Some(&mut 3));
+        assert_eq!(iter.next_back(), None);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next_back(), Some(50));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.collect::<Vec<_>>(), vec![2, 3, 40]);
+    }
+
+    #[test]
+    fn append_and_split_off() {
+        let mut list: List<i32> = (1..=3).collect();
+        let mut other: List<i32> = (4..=6).collect();
+        list.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(other.peek_back(), None);
+        assert_eq!(list.len(), 6);
+        // the seam is linked both ways
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            vec![6, 5, 4, 3, 2, 1]
+        );
+
+        // appending an empty list, or onto one, leaves everything consistent
+        list.append(&mut other);
+        other.append(&mut list);
+        assert!(list.is_empty());
+        assert_eq!(other.len(), 6);
+        assert_eq!(other.peek_front(), Some(&1));
+        assert_eq!(other.peek_back(), Some(&6));
+
+        // cut near the front, near the back, and at both ends
+        let mut list = other;
+        let back = list.split_off(4);
+        assert_eq!(back.iter().copied().collect::<Vec<_>>(), vec![5, 6]);
+        let mut middle = list.split_off(1);
+        assert_eq!(
+            middle.iter().rev().copied().collect::<Vec<_>>(),
+            vec![4, 3, 2]
+        );
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1]);
+        assert!(middle.split_off(3).is_empty());
+        let all = middle.split_off(0);
+        assert!(middle.is_empty());
+        assert_eq!(all.len(), 3);
+
+        // the halves are independent lists
+        list.push_back(10);
+        let mut back = back;
+        back.push_front(20);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 10]);
+        assert_eq!(back.iter().copied().collect::<Vec<_>>(), vec![20, 5, 6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "split_off index out of bounds")]
+    fn split_off_out_of_bounds() {
+        let mut list: List<i32> = (1..=3).collect();
+        list.split_off(4);
+    }
+
+    #[test]
+    fn contains() {
+        let list: List<i32> = (1..=3).collect();
+        assert!(list.contains(&2));
+        assert!(!list.contains(&4));
+        assert!(!List::new().contains(&1));
+    }
+
+    #[test]
+    fn std_traits() {
+        let list: List<i32> = vec![1, 2, 3].into_iter().collect();
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+
+        let mut copy = list.clone();
+        assert_eq!(copy, list);
+        assert_eq!(hash(&copy), hash(&list));
+
+        copy.push_back(4);
+        assert_ne!(copy, list);
+        assert!(copy > list);
+        copy.pop_front();
+        assert!(copy > list);
+        copy.extend(vec![5, 6]);
+        assert_eq!(format!("{:?}", copy), "[2, 3, 4, 5, 6]");
+        assert_eq!(List::<i32>::default(), List::new());
+
+        for elem in &mut copy {
+            *elem *= 2;
+        }
+        let elems: Vec<_> = (&copy).into_iter().copied().collect();
+        assert_eq!(elems, vec![4, 6, 8, 10, 12]);
+
+        copy.clear();
+        assert!(copy.is_empty());
+        assert_eq!(copy.peek_back(), None);
+    }
+
+    #[test]
+    fn drops_every_element_once() {
+        use std::rc::Rc;
+
+        let elem = Rc::new(());
+        let mut list: List<_> = (0..10).map(|_| elem.clone()).collect();
+        list.pop_front();
+        list.pop_back();
+        assert_eq!(Rc::strong_count(&elem), 9);
+
+        let mut iter = list.into_iter();
+        iter.next();
+        iter.next_back();
+        assert_eq!(Rc::strong_count(&elem), 7);
+        drop(iter);
+        assert_eq!(Rc::strong_count(&elem), 1);
+    }
+
+    // These only need to compile. A list of longer-lived references must be
+    // usable as a list of shorter-lived ones, like any std collection, and
+    // everything is as Send and Sync as the references it hands out.
+    #[test]
+    fn variance_and_auto_traits() {
+        fn list<'a>(list: List<&'static str>) -> List<&'a str> {
+            list
+        }
+        fn iter<'i, 'a>(iter: Iter<'i, &'static str>) -> Iter<'i, &'a str> {
+            iter
+        }
+        fn into_iter<'a>(iter: IntoIter<&'static str>) -> IntoIter<&'a str> {
+            iter
+        }
+
+        fn send_sync<T: Send + Sync>(_: &T) {}
+
+        let mut elems = list(List::new());
+        elems.push_back("covariant");
+        send_sync(&elems);
+        send_sync(&iter(elems.iter()));
+        send_sync(&elems.iter_mut());
+        send_sync(&into_iter(elems.into_iter()));
+    }
+}
@@ -0,0 +1,286 @@
+use std::borrow::Borrow;
+use std::cell::{Ref, RefMut};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::mem;
+
+use crate::fourth::{self, List, NodeHandle};
+
+// A least-recently-used cache: a `HashMap` to find entries, and `fourth`'s
+// deque to keep them in recency order, most recent at the front. The map
+// holds a handle to each entry's node, so touching an entry is just moving
+// its node to the front, and evicting is popping the back.
+//
+// Keys live in both the map and the node (the node needs its key to remove
+// the map entry when it's evicted), hence `K: Clone`.
+pub struct LruCache<K, V> {
+    map: HashMap<K, NodeHandle<(K, V)>>,
+    order: List<(K, V)>,
+    capacity: usize,
+    on_evict: Option<Box<dyn FnMut(K, V)>>,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "an LRU cache needs room for one entry");
+        LruCache {
+            map: HashMap::new(),
+            order: List::new(),
+            capacity,
+            on_evict: None,
+        }
+    }
+
+    // `on_evict` gets every entry pushed out to make room. It isn't called
+    // for entries taken out with `pop_lru` or `remove`; those are handed
+    // straight back anyway.
+    pub fn with_on_evict<F: FnMut(K, V) + 'static>(capacity: usize, on_evict: F) -> Self {
+        let mut cache = Self::new(capacity);
+        cache.on_evict = Some(Box::new(on_evict));
+        cache
+    }
+
+    // Marks the entry as the most recently used.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<Ref<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = self.map.get(key)?;
+        self.order.move_to_front(handle);
+        self.order
+            .get(handle)
+            .map(|entry| Ref::map(entry, |(_, v)| v))
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<RefMut<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = self.map.get(key)?;
+        self.order.move_to_front(handle);
+        self.order
+            .get_mut(handle)
+            .map(|entry| RefMut::map(entry, |(_, v)| v))
+    }
+
+    // Like `get`, but leaves the recency order alone.
+    pub fn peek<Q>(&self, key: &Q) -> Option<Ref<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = self.map.get(key)?;
+        self.order
+            .get(handle)
+            .map(|entry| Ref::map(entry, |(_, v)| v))
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    // Inserts or updates the entry and makes it the most recent. Returns the
+    // old value if the key was already there; otherwise, if the cache was
+    // full, the least recent entry is evicted to make room.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(handle) = self.map.get(&key) {
+            self.order.move_to_front(handle);
+            let mut entry = self.order.get_mut(handle).unwrap();
+            return Some(mem::replace(&mut entry.1, value));
+        }
+
+        if self.order.len() == self.capacity {
+            if let Some((key, value)) = self.pop_lru() {
+                if let Some(on_evict) = &mut self.on_evict {
+                    on_evict(key, value);
+                }
+            }
+        }
+        let handle = self.order.push_front_handle((key.clone(), value));
+        self.map.insert(key, handle);
+        None
+    }
+
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.order.pop_back()?;
+        self.map.remove(&key);
+        Some((key, value))
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = self.map.remove(key)?;
+        self.order.remove(&handle).map(|(_, v)| v)
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    // From the most to the least recently used, without touching the order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter(self.order.iter())
+    }
+}
+
+pub struct Iter<'a, K, V>(fourth::Iter<'a, (K, V)>);
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (Ref<'a, K>, Ref<'a, V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .next()
+            .map(|entry| Ref::map_split(entry, |(k, v)| (k, v)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0
+            .next_back()
+            .map(|entry| Ref::map_split(entry, |(k, v)| (k, v)))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+#[cfg(test)]
+mod test {
+    use super::LruCache;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn keys(cache: &LruCache<&'static str, i32>) -> Vec<&'static str> {
+        cache.iter().map(|(k, _)| *k).collect()
+    }
+
+    #[test]
+    fn basics() {
+        let mut cache = LruCache::new(2);
+        assert!(cache.is_empty());
+        assert!(cache.get("a").is_none());
+
+        assert_eq!(cache.put("a", 1), None);
+        assert_eq!(cache.put("b", 2), None);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(keys(&cache), vec!["b", "a"]);
+
+        // getting "a" makes "b" the one to go
+        assert_eq!(*cache.get("a").unwrap(), 1);
+        assert_eq!(keys(&cache), vec!["a", "b"]);
+        assert_eq!(cache.put("c", 3), None);
+        assert!(!cache.contains_key("b"));
+        assert_eq!(keys(&cache), vec!["c", "a"]);
+
+        // updating an entry refreshes it without evicting anything
+        assert_eq!(cache.put("a", 10), Some(1));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(keys(&cache), vec!["a", "c"]);
+
+        *cache.get_mut("c").unwrap() += 1;
+        assert_eq!(
+            cache.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec![4, 10]
+        );
+        assert_eq!(cache.capacity(), 2);
+    }
+
+    #[test]
+    fn peek_leaves_order_alone() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        assert_eq!(*cache.peek("a").unwrap(), 1);
+        assert!(cache.peek("z").is_none());
+        cache.put("c", 3);
+        assert!(cache.peek("a").is_none());
+        assert_eq!(keys(&cache), vec!["c", "b"]);
+    }
+
+    #[test]
+    fn pop_and_remove() {
+        let mut cache = LruCache::new(3);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("c", 3);
+
+        assert_eq!(cache.remove("b"), Some(2));
+        assert_eq!(cache.remove("b"), None);
+        assert_eq!(cache.pop_lru(), Some(("a", 1)));
+        assert_eq!(cache.pop_lru(), Some(("c", 3)));
+        assert_eq!(cache.pop_lru(), None);
+        assert!(cache.is_empty());
+
+        // still usable, and at full capacity again
+        for (k, v) in [("x", 1), ("y", 2), ("z", 3)] {
+            cache.put(k, v);
+        }
+        assert_eq!(keys(&cache), vec!["z", "y", "x"]);
+        assert_eq!(
+            cache.iter().rev().map(|(k, _)| *k).collect::<Vec<_>>(),
+            vec!["x", "y", "z"]
+        );
+    }
+
+    #[test]
+    fn on_evict() {
+        let evicted = Rc::new(RefCell::new(Vec::new()));
+        let mut cache = {
+            let evicted = evicted.clone();
+            LruCache::with_on_evict(2, move |k, v| evicted.borrow_mut().push((k, v)))
+        };
+
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("a", 3);
+        assert!(evicted.borrow().is_empty());
+
+        cache.put("c", 4);
+        cache.get("a");
+        cache.put("d", 5);
+        assert_eq!(*evicted.borrow(), vec![("b", 2), ("c", 4)]);
+
+        // only evictions count, not entries taken out by hand
+        cache.pop_lru();
+        cache.remove("d");
+        assert_eq!(evicted.borrow().len(), 2);
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let mut cache = LruCache::new(2);
+        cache.put(String::from("a"), 1);
+        cache.put(String::from("b"), 2);
+        assert_eq!(*cache.get("a").unwrap(), 1);
+        assert_eq!(cache.remove("b"), Some(2));
+        assert!(cache.contains_key("a"));
+    }
+
+    #[test]
+    #[should_panic(expected = "an LRU cache needs room for one entry")]
+    fn zero_capacity() {
+        LruCache::<i32, i32>::new(0);
+    }
+}
@@ -0,0 +1,474 @@
+// Differential testing: replay random operation sequences against a list and
+// against a std collection that is known to be right, and complain as soon as
+// the two disagree. A failing sequence is shrunk before it's reported, so the
+// panic message is a short reproducer rather than a wall of random pushes.
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+const SEED: u64 = 0x5eed_1157_c0ff_ee00;
+const CASES: usize = 500;
+const MAX_LEN: u64 = 64;
+
+// splitmix64; small, fast, and the same sequence on every machine
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    PushFront(i32),
+    PushBack(i32),
+    PopFront,
+    PopBack,
+    PeekFront,
+    PeekBack,
+    Len,
+    Iter,
+}
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Done,
+    Elem(Option<i32>),
+    Len(usize),
+    Elems(Vec<i32>),
+}
+
+// Anything ops can be replayed against: the lists under test and the models.
+// `OPS` lists the operations the subject supports; the values inside the
+// pushes are placeholders that get replaced when generating.
+trait Subject: Default {
+    const OPS: &'static [Op];
+
+    fn apply(&mut self, op: Op) -> Outcome;
+}
+
+// The stack model; the top of the stack is the end of the Vec.
+impl Subject for Vec<i32> {
+    const OPS: &'static [Op] = &[
+        Op::PushFront(0),
+        Op::PopFront,
+        Op::PeekFront,
+        Op::Len,
+        Op::Iter,
+    ];
+
+    fn apply(&mut self, op: Op) -> Outcome {
+        match op {
+            Op::PushFront(elem) => {
+                self.push(elem);
+                Outcome::Done
+            }
+            Op::PopFront => Outcome::Elem(self.pop()),
+            Op::PeekFront => Outcome::Elem(self.last().copied()),
+            Op::Len => Outcome::Len(self.len()),
+            Op::Iter => Outcome::Elems(self.iter().rev().copied().collect()),
+            _ => unreachable!("{:?} is not a stack operation", op),
+        }
+    }
+}
+
+// The queue and deque model.
+impl Subject for VecDeque<i32> {
+    const OPS: &'static [Op] = &[
+        Op::PushFront(0),
+        Op::PushBack(0),
+        Op::PopFront,
+        Op::PopBack,
+        Op::PeekFront,
+        Op::PeekBack,
+        Op::Len,
+        Op::Iter,
+    ];
+
+    fn apply(&mut self, op: Op) -> Outcome {
+        match op {
+            Op::PushFront(elem) => {
+                self.push_front(elem);
+                Outcome::Done
+            }
+            Op::PushBack(elem) => {
+                self.push_back(elem);
+                Outcome::Done
+            }
+            Op::PopFront => Outcome::Elem(self.pop_front()),
+            Op::PopBack => Outcome::Elem(self.pop_back()),
+            Op::PeekFront => Outcome::Elem(self.front().copied()),
+            Op::PeekBack => Outcome::Elem(self.back().copied()),
+            Op::Len => Outcome::Len(self.len()),
+            Op::Iter => Outcome::Elems(self.iter().copied().collect()),
+        }
+    }
+}
+
+#[cfg(feature = "stack")]
+impl Subject for crate::stack::List<i32> {
+    const OPS: &'static [Op] = &[
+        Op::PushFront(0),
+        Op::PopFront,
+        Op::PeekFront,
+        Op::Len,
+        Op::Iter,
+    ];
+
+    fn apply(&mut self, op: Op) -> Outcome {
+        match op {
+            Op::PushFront(elem) => {
+                self.push(elem);
+                Outcome::Done
+            }
+            Op::PopFront => Outcome::Elem(self.pop()),
+            Op::PeekFront => Outcome::Elem(self.peek().copied()),
+            Op::Len => Outcome::Len(crate::traits::Collection::len(self)),
+            Op::Iter => Outcome::Elems(self.iter().copied().collect()),
+            _ => unreachable!("{:?} is not a stack operation", op),
+        }
+    }
+}
+
+#[cfg(feature = "queue")]
+impl Subject for crate::queue::List<i32> {
+    const OPS: &'static [Op] = &[
+        Op::PushBack(0),
+        Op::PopFront,
+        Op::PeekFront,
+        Op::Len,
+        Op::Iter,
+    ];
+
+    fn apply(&mut self, op: Op) -> Outcome {
+        match op {
+            Op::PushBack(elem) => {
+                self.push(elem);
+                Outcome::Done
+            }
+            Op::PopFront => Outcome::Elem(self.pop()),
+            Op::PeekFront => Outcome::Elem(self.peek().copied()),
+            Op::Len => Outcome::Len(crate::traits::Collection::len(self)),
+            Op::Iter => Outcome::Elems(self.iter().copied().collect()),
+            _ => unreachable!("{:?} is not a queue operation", op),
+        }
+    }
+}
+
+#[cfg(feature = "rc_deque")]
+impl Subject for crate::rc_deque::List<i32> {
+    const OPS: &'static [Op] = <VecDeque<i32> as Subject>::OPS;
+
+    fn apply(&mut self, op: Op) -> Outcome {
+        match op {
+            Op::PushFront(elem) => {
+                self.push_front(elem);
+                Outcome::Done
+            }
+            Op::PushBack(elem) => {
+                self.push_back(elem);
+                Outcome::Done
+            }
+            Op::PopFront => Outcome::Elem(self.pop_front()),
+            Op::PopBack => Outcome::Elem(self.pop_back()),
+            Op::PeekFront => Outcome::Elem(self.peek_front().map(|elem| *elem)),
+            Op::PeekBack => Outcome::Elem(self.peek_back().map(|elem| *elem)),
+            Op::Len => Outcome::Len(crate::traits::Collection::len(self)),
+            Op::Iter => Outcome::Elems(self.iter().map(|elem| *elem).collect()),
+        }
+    }
+}
+
+#[cfg(feature = "linked_deque")]
+impl Subject for crate::linked_deque::List<i32> {
+    const OPS: &'static [Op] = <VecDeque<i32> as Subject>::OPS;
+
+    fn apply(&mut self, op: Op) -> Outcome {
+        match op {
+            Op::PushFront(elem) => {
+                self.push_front(elem);
+                Outcome::Done
+            }
+            Op::PushBack(elem) => {
+                self.push_back(elem);
+                Outcome::Done
+            }
+            Op::PopFront => Outcome::Elem(self.pop_front()),
+            Op::PopBack => Outcome::Elem(self.pop_back()),
+            Op::PeekFront => Outcome::Elem(self.peek_front().copied()),
+            Op::PeekBack => Outcome::Elem(self.peek_back().copied()),
+            Op::Len => Outcome::Len(crate::traits::Collection::len(self)),
+            Op::Iter => Outcome::Elems(self.iter().copied().collect()),
+        }
+    }
+}
+
+fn generate(rng: &mut Rng, ops: &[Op]) -> Vec<Op> {
+    let len = rng.below(MAX_LEN) + 1;
+    (0..len)
+        .map(|_| {
+            let elem = rng.below(100) as i32;
+            match ops[rng.below(ops.len() as u64) as usize] {
+                Op::PushFront(_) => Op::PushFront(elem),
+                Op::PushBack(_) => Op::PushBack(elem),
+                op => op,
+            }
+        })
+        .collect()
+}
+
+thread_local! {
+    // Set while this thread is inside `replay`, whose panics are caught and
+    // reported as part of the reproducer.
+    static REPLAYING: Cell<bool> = const { Cell::new(false) };
+}
+
+// Installs, once for the whole process, a panic hook that keeps quiet on a
+// thread that's replaying and hands everything else to the hook that was
+// there before. Swapping the hook in and out around each shrink would also
+// silence every other test running in parallel, and two shrinks at once
+// could leave the quiet hook behind for good.
+fn quiet_replays() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !REPLAYING.with(Cell::get) {
+                previous(info);
+            }
+        }));
+    });
+}
+
+// Replays `ops` against a fresh subject and model, returning a description of
+// the first step where they disagree. A panic in the subject counts as
+// disagreeing; the shrinker replays a lot of panicking sequences, so it's
+// reported here rather than printed with a backtrace every time.
+fn replay<S: Subject, M: Subject>(ops: &[Op]) -> Option<String> {
+    quiet_replays();
+    let mut subject = S::default();
+    let mut model = M::default();
+    for (step, &op) in ops.iter().enumerate() {
+        let expected = model.apply(op);
+        REPLAYING.with(|replaying| replaying.set(true));
+        let actual = panic::catch_unwind(AssertUnwindSafe(|| subject.apply(op)));
+        REPLAYING.with(|replaying| replaying.set(false));
+        match actual {
+            Ok(actual) if actual == expected => {}
+            Ok(actual) => {
+                return Some(format!(
+                    "step {}: {:?} gave {:?}, expected {:?}",
+                    step, op, actual, expected
+                ))
+            }
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                    .unwrap_or("non-string payload");
+                return Some(format!("step {}: {:?} panicked: {}", step, op, message));
+            }
+        }
+    }
+    None
+}
+
+// Pushes of smaller values, for shrinking.
+fn simpler(op: Op) -> Option<Op> {
+    match op {
+        Op::PushFront(elem) if elem != 0 => Some(Op::PushFront(elem / 2)),
+        Op::PushBack(elem) if elem != 0 => Some(Op::PushBack(elem / 2)),
+        _ => None,
+    }
+}
+
+// Greedily cuts `ops` down while it still fails: first by dropping ever
+// smaller runs of ops, then by shrinking the pushed values, until neither
+// makes progress.
+fn shrink<S: Subject, M: Subject>(mut ops: Vec<Op>) -> Vec<Op> {
+    let fails = |ops: &[Op]| replay::<S, M>(ops).is_some();
+    loop {
+        let mut shrunk = false;
+
+        let mut chunk = ops.len() / 2;
+        while chunk > 0 {
+            let mut start = 0;
+            while start + chunk <= ops.len() {
+                let mut candidate = ops.clone();
+                candidate.drain(start..start + chunk);
+                if fails(&candidate) {
+                    ops = candidate;
+                    shrunk = true;
+                } else {
+                    start += chunk;
+                }
+            }
+            chunk /= 2;
+        }
+
+        for i in 0..ops.len() {
+            while let Some(op) = simpler(ops[i]) {
+                let mut candidate = ops.clone();
+                candidate[i] = op;
+                if !fails(&candidate) {
+                    break;
+                }
+                ops = candidate;
+                shrunk = true;
+            }
+        }
+
+        if !shrunk {
+            return ops;
+        }
+    }
+}
+
+fn check<S: Subject, M: Subject>(name: &str) {
+    let mut rng = Rng(SEED);
+    for case in 0..CASES {
+        let ops = generate(&mut rng, S::OPS);
+        if replay::<S, M>(&ops).is_none() {
+            continue;
+        }
+
+        let ops = shrink::<S, M>(ops);
+
+        let mut report = format!(
+            "{} diverged from its model in case {}; minimal reproducer:\n",
+            name, case
+        );
+        for op in &ops {
+            writeln!(report, "    {:?}", op).unwrap();
+        }
+        report.push_str(&replay::<S, M>(&ops).unwrap());
+        panic!("{}", report);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check, replay, shrink, Op, Outcome, Subject, REPLAYING};
+    use std::collections::VecDeque;
+
+    #[test]
+    #[cfg(feature = "stack")]
+    fn stack() {
+        check::<crate::stack::List<i32>, Vec<i32>>("stack::List");
+    }
+
+    #[test]
+    #[cfg(feature = "queue")]
+    fn queue() {
+        check::<crate::queue::List<i32>, VecDeque<i32>>("queue::List");
+    }
+
+    #[test]
+    #[cfg(feature = "rc_deque")]
+    fn rc_deque() {
+        check::<crate::rc_deque::List<i32>, VecDeque<i32>>("rc_deque::List");
+    }
+
+    #[test]
+    #[cfg(feature = "linked_deque")]
+    fn linked_deque() {
+        check::<crate::linked_deque::List<i32>, VecDeque<i32>>("linked_deque::List");
+    }
+
+    // A queue with the classic stale-tail bug: once it has been emptied, the
+    // next push goes nowhere.
+    #[derive(Default)]
+    struct StaleTail {
+        elems: VecDeque<i32>,
+        drained: bool,
+    }
+
+    impl Subject for StaleTail {
+        const OPS: &'static [Op] = &[Op::PushBack(0), Op::PopFront, Op::PeekFront];
+
+        fn apply(&mut self, op: Op) -> Outcome {
+            match op {
+                Op::PushBack(elem) => {
+                    if !self.drained {
+                        self.elems.push_back(elem);
+                    }
+                    Outcome::Done
+                }
+                Op::PopFront => {
+                    let elem = self.elems.pop_front();
+                    self.drained |= elem.is_some() && self.elems.is_empty();
+                    Outcome::Elem(elem)
+                }
+                Op::PeekFront => Outcome::Elem(self.elems.front().copied()),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn shrinks_to_minimal_reproducer() {
+        let ops = vec![
+            Op::PushBack(17),
+            Op::PushBack(42),
+            Op::PeekFront,
+            Op::PopFront,
+            Op::PushBack(3),
+            Op::PopFront,
+            Op::PopFront,
+            Op::PushBack(99),
+            Op::PeekFront,
+            Op::PushBack(5),
+        ];
+        assert!(replay::<StaleTail, VecDeque<i32>>(&ops).is_some());
+
+        let ops = shrink::<StaleTail, VecDeque<i32>>(ops);
+        assert_eq!(ops.len(), 4);
+        assert_eq!(ops[0], Op::PushBack(0));
+        assert_eq!(ops[1], Op::PopFront);
+        assert!(ops[2] == Op::PushBack(0) || ops[2] == Op::PushBack(1));
+        assert!(replay::<StaleTail, VecDeque<i32>>(&ops).is_some());
+    }
+
+    // Pops panic instead of returning.
+    #[derive(Default)]
+    struct PanicsOnPop;
+
+    impl Subject for PanicsOnPop {
+        const OPS: &'static [Op] = &[Op::PushBack(0), Op::PopFront];
+
+        fn apply(&mut self, op: Op) -> Outcome {
+            match op {
+                Op::PushBack(_) => Outcome::Done,
+                _ => panic!("pop on {:?}", op),
+            }
+        }
+    }
+
+    #[test]
+    fn panics_are_reported_not_printed() {
+        let ops = [Op::PushBack(1), Op::PopFront];
+        let report = replay::<PanicsOnPop, VecDeque<i32>>(&ops).unwrap();
+        assert_eq!(report, "step 1: PopFront panicked: pop on PopFront");
+        // only the replay itself is quiet
+        assert!(!REPLAYING.with(|replaying| replaying.get()));
+    }
+
+    #[test]
+    #[should_panic(expected = "minimal reproducer")]
+    fn reports_divergence() {
+        check::<StaleTail, VecDeque<i32>>("StaleTail");
+    }
+}
@@ -0,0 +1,450 @@
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering::*};
+use std::sync::OnceLock;
+
+use crate::hazard::Domain;
+
+// A Michael-Scott queue: `fifth`'s head/tail queue with both ends made atomic.
+//
+// `head` always points at a dummy node whose element has already been taken
+// (or never existed); the first real element lives in `head.next`. Popping
+// swings `head` forward and takes the element out of the node it lands on,
+// which becomes the next dummy. Because there's always at least the dummy,
+// `head` and `tail` are never null and pushers and poppers only ever meet on
+// the dummy when the queue is empty.
+//
+// As with the stack, nothing ever waits: a pop that finds a `peek` still
+// cloning the element it's taking clones it as well, and leaves the original
+// in the node to be dropped when the node is freed.
+pub struct List<T> {
+    head: AtomicPtr<Node<T>>,
+    tail: AtomicPtr<Node<T>>,
+    len: AtomicUsize,
+    // Protects nodes while push, pop and peek walk through them.
+    hazards: Domain,
+    // `T::clone`, once some `peek` has shown there is one.
+    clone: OnceLock<fn(&T) -> T>,
+    _boo: PhantomData<T>,
+}
+
+// Set in `Node::readers` once the node's element has been popped, so later
+// peeks know to leave it alone.
+const POPPED: usize = 1 << (usize::BITS - 1);
+
+// `elem` is uninitialised in the first dummy, and moved out of most nodes by
+// the pop that makes them the dummy; `has_elem` says which. A node whose
+// element is still there drops it when it's freed.
+struct Node<T> {
+    elem: MaybeUninit<T>,
+    next: AtomicPtr<Node<T>>,
+    // How many peeks are cloning `elem`, plus `POPPED`.
+    readers: AtomicUsize,
+    has_elem: AtomicBool,
+}
+
+impl<T> Node<T> {
+    fn new(elem: Option<T>) -> *mut Self {
+        Box::into_raw(Box::new(Node {
+            has_elem: AtomicBool::new(elem.is_some()),
+            elem: elem.map_or(MaybeUninit::uninit(), MaybeUninit::new),
+            next: AtomicPtr::new(ptr::null_mut()),
+            readers: AtomicUsize::new(0),
+        }))
+    }
+}
+
+impl<T> Drop for Node<T> {
+    fn drop(&mut self) {
+        if *self.has_elem.get_mut() {
+            unsafe { ptr::drop_in_place(self.elem.as_mut_ptr()) };
+        }
+    }
+}
+
+// Same reasoning as the stack: push and pop only move Ts between threads,
+// and `peek` asks for T: Sync itself.
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Send> Sync for List<T> {}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        let dummy = Node::new(None);
+        List {
+            head: AtomicPtr::new(dummy),
+            tail: AtomicPtr::new(dummy),
+            len: AtomicUsize::new(0),
+            hazards: Domain::new(),
+            clone: OnceLock::new(),
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn push(&self, elem: T) {
+        let new_tail = Node::new(Some(elem));
+        // Counted before it's linked in, since a pop can take it the moment
+        // it is; counting afterwards could let `len` wrap.
+        self.len.fetch_add(1, Relaxed);
+        let hazard = self.hazards.hazard();
+        loop {
+            let tail = hazard.protect(&self.tail);
+            let next = unsafe { (*tail).next.load(Acquire) };
+            if next.is_null() {
+                // Linking the node in is what actually pushes it...
+                let linked = unsafe {
+                    (*tail)
+                        .next
+                        .compare_exchange(ptr::null_mut(), new_tail, Release, Relaxed)
+                        .is_ok()
+                };
+                if linked {
+                    // ...moving `tail` up is just tidying, and if we lose the
+                    // race someone else has already done it for us.
+                    let _ = self.tail.compare_exchange(tail, new_tail, Release, Relaxed);
+                    break;
+                }
+            } else {
+                // `tail` is lagging behind a push that hasn't tidied up yet;
+                // help it along rather than waiting.
+                let _ = self.tail.compare_exchange(tail, next, Release, Relaxed);
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let head_hazard = self.hazards.hazard();
+        let next_hazard = self.hazards.hazard();
+        loop {
+            let head = head_hazard.protect(&self.head);
+            let next = next_hazard.protect(unsafe { &(*head).next });
+            // `next` is only safe to use if it was still head's successor
+            // while `head` was still the head.
+            if self.head.load(SeqCst) != head {
+                continue;
+            }
+            if next.is_null() {
+                return None;
+            }
+            if head == self.tail.load(Acquire) {
+                // A push has linked `next` but not moved `tail` yet. Don't
+                // let `head` overtake `tail`; help the push finish first.
+                let _ = self.tail.compare_exchange(head, next, Release, Relaxed);
+                continue;
+            }
+
+            if self
+                .head
+                .compare_exchange(head, next, AcqRel, Acquire)
+                .is_ok()
+            {
+                self.len.fetch_sub(1, Relaxed);
+                // `next` is the new dummy, but its element is ours. As in the
+                // stack, we clone it if a `peek` is still cloning it, and move
+                // it out otherwise. `next_hazard` keeps the node itself alive,
+                // even if another pop retires it.
+                unsafe {
+                    let elem = if (*next).readers.fetch_or(POPPED, AcqRel) == 0 {
+                        (*next).has_elem.store(false, Relaxed);
+                        ptr::read((*next).elem.as_ptr())
+                    } else {
+                        let clone = self.clone.get().expect("only a peek reads elements");
+                        clone(&*(*next).elem.as_ptr())
+                    };
+                    self.hazards.retire(head);
+                    return Some(elem);
+                }
+            }
+        }
+    }
+
+    // Like the stack, you get a copy of the front element, not a reference.
+    pub fn peek(&self) -> Option<T>
+    where
+        T: Clone + Sync,
+    {
+        self.clone.get_or_init(|| T::clone);
+        let head_hazard = self.hazards.hazard();
+        let next_hazard = self.hazards.hazard();
+        loop {
+            let head = head_hazard.protect(&self.head);
+            let next = next_hazard.protect(unsafe { &(*head).next });
+            if self.head.load(SeqCst) != head {
+                continue;
+            }
+            if next.is_null() {
+                return None;
+            }
+            unsafe {
+                if (*next).readers.fetch_add(1, AcqRel) & POPPED != 0 {
+                    continue;
+                }
+                let elem = (*(*next).elem.as_ptr()).clone();
+                (*next).readers.fetch_sub(1, Release);
+                return Some(elem);
+            }
+        }
+    }
+
+    // Only a snapshot: other threads may push or pop before you use it, and
+    // a push that's still linking its node in is already counted.
+    pub fn len(&self) -> usize {
+        self.len.load(Relaxed)
+    }
+
+    // Looks at the queue itself rather than `len`, which can run ahead of it.
+    pub fn is_empty(&self) -> bool {
+        let hazard = self.hazards.hazard();
+        let head = hazard.protect(&self.head);
+        unsafe { (*head).next.load(Acquire).is_null() }
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Nodes free their own elements, including a dummy whose element a pop had
+// to clone rather than take.
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        let mut cur = *self.head.get_mut();
+        while !cur.is_null() {
+            let mut node = unsafe { Box::from_raw(cur) };
+            cur = *node.next.get_mut();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::List;
+    use crate::test_util::{Drops, Stuck};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering::*};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    const PRODUCERS: usize = 4;
+    const CONSUMERS: usize = 4;
+    const PER_PRODUCER: usize = 10_000;
+
+    #[test]
+    fn basics() {
+        let list = List::new();
+
+        // Check empty list behaves right
+        assert_eq!(list.pop(), None);
+
+        // Populate list
+        list.push(1);
+        list.push(2);
+        list.push(3);
+
+        // Check normal removal
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(list.pop(), Some(2));
+
+        // Push some more just to make sure nothing's corrupted
+        list.push(4);
+        list.push(5);
+
+        // Check normal removal
+        assert_eq!(list.pop(), Some(3));
+        assert_eq!(list.pop(), Some(4));
+
+        // Check exhaustion
+        assert_eq!(list.pop(), Some(5));
+        assert_eq!(list.pop(), None);
+
+        // Check the exhaustion case fixed the pointer right
+        list.push(6);
+        list.push(7);
+
+        // Check normal removal
+        assert_eq!(list.pop(), Some(6));
+        assert_eq!(list.pop(), Some(7));
+        assert_eq!(list.pop(), None);
+    }
+
+    #[test]
+    fn peek() {
+        let list = List::new();
+        assert_eq!(list.peek(), None);
+        assert!(list.is_empty());
+
+        list.push(1);
+        list.push(2);
+        assert_eq!(list.peek(), Some(1));
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(list.peek(), Some(2));
+        assert_eq!(list.pop(), Some(2));
+        assert_eq!(list.peek(), None);
+    }
+
+    #[test]
+    fn drops_every_element_once() {
+        let drops = Drops::new();
+        let list = List::new();
+        for _ in 0..200 {
+            list.push(drops.track());
+        }
+        for _ in 0..150 {
+            drop(list.pop());
+        }
+        assert_eq!(drops.dropped(), 150);
+        drop(list);
+        assert_eq!(drops.dropped(), 200);
+    }
+
+    // The original goes with its node once the pop has cloned it.
+    #[test]
+    fn pop_does_not_wait_for_peek() {
+        let stuck = Stuck::new();
+        let list = Arc::new(List::new());
+        list.push(stuck.share());
+
+        let peeker = {
+            let list = list.clone();
+            thread::spawn(move || list.peek().is_some())
+        };
+        stuck.wait_halfway();
+        let popped = list.pop();
+        assert!(popped.is_some());
+        assert!(list.is_empty());
+        stuck.release();
+        assert!(peeker.join().unwrap());
+
+        drop(popped);
+        drop(list);
+        assert_eq!(stuck.alive(), 1);
+    }
+
+    // Producers push (producer, sequence number) pairs. Every pair must come
+    // out exactly once, and each consumer must see any one producer's pairs
+    // in the order they were pushed.
+    #[test]
+    fn stress_mpmc() {
+        let list = Arc::new(List::new());
+        let popped = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(Barrier::new(PRODUCERS + CONSUMERS));
+
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|p| {
+                let list = list.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..PER_PRODUCER {
+                        list.push((p, i));
+                    }
+                })
+            })
+            .collect();
+
+        let consumers: Vec<_> = (0..CONSUMERS)
+            .map(|_| {
+                let list = list.clone();
+                let popped = popped.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    let mut last = [None; PRODUCERS];
+                    let mut seen = Vec::new();
+                    while popped.load(SeqCst) < PRODUCERS * PER_PRODUCER {
+                        if let Some((p, i)) = list.pop() {
+                            popped.fetch_add(1, SeqCst);
+                            assert!(last[p] < Some(i), "producer {} out of order", p);
+                            last[p] = Some(i);
+                            seen.push((p, i));
+                        }
+                    }
+                    seen
+                })
+            })
+            .collect();
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut seen = vec![vec![false; PER_PRODUCER]; PRODUCERS];
+        for consumer in consumers {
+            for (p, i) in consumer.join().unwrap() {
+                assert!(!seen[p][i], "({}, {}) popped twice", p, i);
+                seen[p][i] = true;
+            }
+        }
+        assert!(seen.iter().flatten().all(|&seen| seen));
+        assert_eq!(list.pop(), None);
+    }
+
+    // `len` is read while pushes and pops race each other; an element popped
+    // before its push was counted would show up as a wrapped counter.
+    #[test]
+    fn len_never_wraps() {
+        let list = Arc::new(List::new());
+        let done = Arc::new(AtomicBool::new(false));
+        let watcher = {
+            let list = list.clone();
+            let done = done.clone();
+            thread::spawn(move || {
+                while !done.load(SeqCst) {
+                    let len = list.len();
+                    assert!(len <= PRODUCERS * PER_PRODUCER, "len wrapped to {}", len);
+                }
+            })
+        };
+        let handles: Vec<_> = (0..PRODUCERS)
+            .map(|_| {
+                let list = list.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        list.push(i);
+                        list.pop();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        done.store(true, SeqCst);
+        watcher.join().unwrap();
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+    }
+
+    // Peekers clone `Arc`s out of the front node while other threads pop and
+    // drop it.
+    #[test]
+    fn stress_peek_while_popping() {
+        let list = Arc::new(List::new());
+        let barrier = Arc::new(Barrier::new(PRODUCERS + CONSUMERS));
+        let handles: Vec<_> = (0..PRODUCERS + CONSUMERS)
+            .map(|t| {
+                let list = list.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..PER_PRODUCER {
+                        if t % 2 == 0 {
+                            list.push(Arc::new(i));
+                            drop(list.pop());
+                        } else if let Some(elem) = list.peek() {
+                            assert!(*elem < PER_PRODUCER);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        while let Some(elem) = list.pop() {
+            assert_eq!(Arc::strong_count(&elem), 1);
+        }
+    }
+}
@@ -0,0 +1,599 @@
+use std::fmt;
+use std::iter::{FromIterator, Take};
+
+use crate::third;
+use crate::traits::{Collection, Queue};
+
+// A persistent FIFO made of `third` lists: elements are taken off the front
+// of `front`, and added to the front of `rear`, so `rear` holds the back of
+// the queue in reverse. Whenever `rear` would grow longer than `front`, the
+// two have to be rotated into a new `front` (`front ++ reverse(rear)`) and
+// `rear` starts over empty.
+//
+// Doing that rotation all at once, as Okasaki's batched queue does, is only
+// amortised O(1), and the amortisation breaks as soon as an old version is
+// reused: snoc the same version over and over right before a rotation, and
+// every one of them pays for the whole thing. His banker's queue gets around
+// that with lazy, memoised lists, which `third` isn't. So this is Hood and
+// Melville's real-time queue instead: a rotation is started when it's due,
+// but carried out two steps per `snoc` or `tail`. Meanwhile the old `front`
+// keeps serving `head`, and the rotated list takes its place once it's
+// ready, which is always before the old one runs out. Every operation does a
+// bounded amount of work, so `snoc`, `tail` and `head` are worst-case O(1),
+// whichever version they're called on.
+//
+// Every operation returns a new queue and leaves `self` alone; versions share
+// whatever nodes they have in common. Rotating builds new nodes out of
+// elements that older versions still own, which is why anything that can
+// rotate needs `T: Clone`.
+pub struct List<T> {
+    front: third::List<T>,
+    // Counts the elements still coming out of the front, including those the
+    // rotation has yet to deliver, not just what's in `front`.
+    front_len: usize,
+    rotation: Rotation<T>,
+    rear: third::List<T>,
+    rear_len: usize,
+}
+
+// The state of a rotation building `old front ++ reverse(old rear)`.
+enum Rotation<T> {
+    Idle,
+    // Reverses the old front and the old rear side by side, one element of
+    // each per step. `valid` is how many of the elements moved to `front_rev`
+    // are still in the queue: a `tail` while the rotation is running takes
+    // one of them off the real `front`, so the rotation has to drop it too.
+    // `rear_len` is the length of the old rear.
+    Reversing {
+        valid: usize,
+        front: third::List<T>,
+        front_rev: third::List<T>,
+        rear: third::List<T>,
+        rear_rev: third::List<T>,
+        rear_len: usize,
+    },
+    // Puts the still-valid part of the old front back on top of the reversed
+    // rear, one element per step.
+    Appending {
+        valid: usize,
+        front_rev: third::List<T>,
+        done: third::List<T>,
+        rear_len: usize,
+    },
+    // The new front, ready to replace the old one.
+    Done(third::List<T>),
+}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        List {
+            front: third::List::new(),
+            front_len: 0,
+            rotation: Rotation::Idle,
+            rear: third::List::new(),
+            rear_len: 0,
+        }
+    }
+
+    // The front of the queue is always the head of `front`: a rotation
+    // finishes before the old front can run out.
+    pub fn head(&self) -> Option<&T> {
+        self.front.head()
+    }
+
+    pub fn len(&self) -> usize {
+        self.front_len + self.rear_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.front_len == 0
+    }
+
+    // Front to back. While a rotation is running, the queue is whatever is
+    // left of the old front, then the old rear (from the rotation's copies),
+    // then the new rear.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (rotating, unreversed, reversed) = match &self.rotation {
+            Rotation::Idle | Rotation::Done(_) => (0, Vec::new(), None),
+            Rotation::Reversing {
+                rear,
+                rear_rev,
+                rear_len,
+                ..
+            } => (*rear_len, rear.iter().collect(), Some(rear_rev.iter())),
+            Rotation::Appending { done, rear_len, .. } => {
+                // `done` has the reversed old rear at the bottom, under some of
+                // the old front that's been put back already.
+                let mut reversed = done.iter();
+                for _ in *rear_len..done.iter().count() {
+                    reversed.next();
+                }
+                (*rear_len, Vec::new(), Some(reversed))
+            }
+        };
+        Iter {
+            front: self.front.iter().take(self.front_len - rotating),
+            unreversed,
+            reversed,
+            rear: self.rear.iter().collect(),
+        }
+    }
+}
+
+impl<T: Clone> List<T> {
+    // A queue with `elem` added at the back.
+    pub fn snoc(&self, elem: T) -> List<T> {
+        List {
+            front: self.front.clone(),
+            front_len: self.front_len,
+            rotation: self.rotation.clone(),
+            rear: self.rear.append(elem),
+            rear_len: self.rear_len + 1,
+        }
+        .rebalance()
+    }
+
+    // A queue without its front element; empty if this one is.
+    pub fn tail(&self) -> List<T> {
+        if self.is_empty() {
+            return List::new();
+        }
+        List {
+            front: self.front.tail(),
+            front_len: self.front_len - 1,
+            rotation: self.rotation.invalidate(),
+            rear: self.rear.clone(),
+            rear_len: self.rear_len,
+        }
+        .rebalance()
+    }
+
+    // Starts a rotation if `rear` has got longer than `front`, and moves
+    // whatever rotation is running on by two steps. Two per operation is
+    // enough for a rotation to finish before the old front is used up, and
+    // before `rear` can catch up again.
+    fn rebalance(self) -> List<T> {
+        let mut queue = self;
+        if queue.rear_len > queue.front_len {
+            debug_assert!(matches!(queue.rotation, Rotation::Idle));
+            queue = List {
+                rotation: Rotation::Reversing {
+                    valid: 0,
+                    front: queue.front.clone(),
+                    front_rev: third::List::new(),
+                    rear: queue.rear,
+                    rear_rev: third::List::new(),
+                    rear_len: queue.rear_len,
+                },
+                front: queue.front,
+                front_len: queue.front_len + queue.rear_len,
+                rear: third::List::new(),
+                rear_len: 0,
+            };
+        }
+        match queue.rotation.step().step() {
+            Rotation::Done(front) => List {
+                front,
+                rotation: Rotation::Idle,
+                ..queue
+            },
+            rotation => List { rotation, ..queue },
+        }
+    }
+}
+
+impl<T> Rotation<T> {
+    // Called on every `tail`: the element it takes off the real front is one
+    // the rotation no longer has to put back.
+    fn invalidate(&self) -> Rotation<T> {
+        match self {
+            Rotation::Reversing {
+                valid,
+                front,
+                front_rev,
+                rear,
+                rear_rev,
+                rear_len,
+            } => Rotation::Reversing {
+                valid: valid - 1,
+                front: front.clone(),
+                front_rev: front_rev.clone(),
+                rear: rear.clone(),
+                rear_rev: rear_rev.clone(),
+                rear_len: *rear_len,
+            },
+            // The last element put back is the front of the new front, and
+            // it's the one being taken.
+            Rotation::Appending { valid: 0, done, .. } => Rotation::Done(done.tail()),
+            Rotation::Appending {
+                valid,
+                front_rev,
+                done,
+                rear_len,
+            } => Rotation::Appending {
+                valid: valid - 1,
+                front_rev: front_rev.clone(),
+                done: done.clone(),
+                rear_len: *rear_len,
+            },
+            other => other.clone(),
+        }
+    }
+}
+
+impl<T: Clone> Rotation<T> {
+    fn step(self) -> Rotation<T> {
+        match self {
+            Rotation::Reversing {
+                valid,
+                front,
+                front_rev,
+                rear,
+                rear_rev,
+                rear_len,
+            } => {
+                // The old rear is one longer than the old front, so it still
+                // has an element when the front runs out, and that's its last.
+                let y = rear.head().expect("rotation outran the rear").clone();
+                match front.head() {
+                    Some(x) => Rotation::Reversing {
+                        valid: valid + 1,
+                        front_rev: front_rev.append(x.clone()),
+                        front: front.tail(),
+                        rear: rear.tail(),
+                        rear_rev: rear_rev.append(y),
+                        rear_len,
+                    },
+                    None => Rotation::Appending {
+                        valid,
+                        front_rev,
+                        done: rear_rev.append(y),
+                        rear_len,
+                    },
+                }
+            }
+            Rotation::Appending { valid: 0, done, .. } => Rotation::Done(done),
+            Rotation::Appending {
+                valid,
+                front_rev,
+                done,
+                rear_len,
+            } => {
+                let x = front_rev.head().expect("rotation outran the front").clone();
+                Rotation::Appending {
+                    valid: valid - 1,
+                    front_rev: front_rev.tail(),
+                    done: done.append(x),
+                    rear_len,
+                }
+            }
+            other => other,
+        }
+    }
+}
+
+// The lists inside are shared, not copied, so no `T: Clone` needed.
+impl<T> Clone for Rotation<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Rotation::Idle => Rotation::Idle,
+            Rotation::Reversing {
+                valid,
+                front,
+                front_rev,
+                rear,
+                rear_rev,
+                rear_len,
+            } => Rotation::Reversing {
+                valid: *valid,
+                front: front.clone(),
+                front_rev: front_rev.clone(),
+                rear: rear.clone(),
+                rear_rev: rear_rev.clone(),
+                rear_len: *rear_len,
+            },
+            Rotation::Appending {
+                valid,
+                front_rev,
+                done,
+                rear_len,
+            } => Rotation::Appending {
+                valid: *valid,
+                front_rev: front_rev.clone(),
+                done: done.clone(),
+                rear_len: *rear_len,
+            },
+            Rotation::Done(front) => Rotation::Done(front.clone()),
+        }
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Just shares everything, like cloning a `third` list.
+impl<T> Clone for List<T> {
+    fn clone(&self) -> Self {
+        List {
+            front: self.front.clone(),
+            front_len: self.front_len,
+            rotation: self.rotation.clone(),
+            rear: self.rear.clone(),
+            rear_len: self.rear_len,
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    front: Take<third::Iter<'a, T>>,
+    // The old rear, mid-rotation: the part still to be reversed, popped from
+    // the end, then the part that already has been.
+    unreversed: Vec<&'a T>,
+    reversed: Option<third::Iter<'a, T>>,
+    // `rear` runs back to front, so it's collected up front and popped.
+    rear: Vec<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.front
+            .next()
+            .or_else(|| self.unreversed.pop())
+            .or_else(|| self.reversed.as_mut()?.next())
+            .or_else(|| self.rear.pop())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// Two queues holding the same elements are equal however they happen to be
+// split between `front`, `rear` and a rotation.
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: Clone> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(List::new(), |queue, elem| queue.snoc(elem))
+    }
+}
+
+impl<T> Collection<T> for List<T> {
+    type Ref<'a>
+        = &'a T
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+// Swaps in the new version each time; other versions sharing our nodes
+// don't notice.
+impl<T: Clone> Queue<T> for List<T> {
+    fn enqueue(&mut self, elem: T) {
+        *self = self.snoc(elem);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        let elem = self.head().cloned();
+        *self = self.tail();
+        elem
+    }
+
+    fn front(&self) -> Option<&T> {
+        self.head()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::List;
+    use std::cell::Cell;
+
+    fn elems(queue: &List<i32>) -> Vec<i32> {
+        queue.iter().copied().collect()
+    }
+
+    #[test]
+    fn basics() {
+        let queue = List::new();
+        assert_eq!(queue.head(), None);
+        assert!(queue.tail().is_empty());
+
+        let queue = queue.snoc(1).snoc(2).snoc(3);
+        assert_eq!(queue.head(), Some(&1));
+        assert_eq!(queue.len(), 3);
+
+        let queue = queue.tail();
+        assert_eq!(queue.head(), Some(&2));
+        let queue = queue.snoc(4).snoc(5);
+        assert_eq!(elems(&queue), vec![2, 3, 4, 5]);
+
+        let queue = queue.tail().tail().tail();
+        assert_eq!(queue.head(), Some(&5));
+        let queue = queue.tail();
+        assert!(queue.is_empty());
+        assert_eq!(queue.head(), None);
+        assert_eq!(queue, List::default());
+    }
+
+    // Rotations build new fronts out of old nodes; none of that may show
+    // through in the versions it started from.
+    #[test]
+    fn old_versions_stay_valid() {
+        let v0: List<i32> = (1..=3).collect();
+        let v1 = v0.snoc(4);
+        let v2 = v1.tail();
+        let v3 = v2.snoc(5).snoc(6);
+        let v4 = v3.tail().tail().tail();
+
+        assert_eq!(elems(&v0), vec![1, 2, 3]);
+        assert_eq!(elems(&v1), vec![1, 2, 3, 4]);
+        assert_eq!(elems(&v2), vec![2, 3, 4]);
+        assert_eq!(elems(&v3), vec![2, 3, 4, 5, 6]);
+        assert_eq!(elems(&v4), vec![5, 6]);
+
+        // Branching twice from one version gives two independent queues
+        let left = v2.snoc(10);
+        let right = v2.snoc(20).snoc(21);
+        assert_eq!(elems(&left), vec![2, 3, 4, 10]);
+        assert_eq!(elems(&right), vec![2, 3, 4, 20, 21]);
+        assert_eq!(elems(&v2), vec![2, 3, 4]);
+
+        // Draining one version leaves the others alone
+        let mut drained = right.clone();
+        while !drained.is_empty() {
+            drained = drained.tail();
+        }
+        assert_eq!(elems(&right), vec![2, 3, 4, 20, 21]);
+        assert_eq!(right.len(), 5);
+    }
+
+    // Every version is checked against a Vec model at every step, along a
+    // long run of snocs and tails that rotates many times.
+    #[test]
+    fn matches_a_vec() {
+        let mut versions = vec![(List::new(), Vec::new())];
+        for step in 0..500 {
+            let (queue, model) = versions.last().unwrap().clone();
+            let next = if step % 3 == 2 {
+                let mut model = model;
+                if !model.is_empty() {
+                    model.remove(0);
+                }
+                (queue.tail(), model)
+            } else {
+                let mut model = model;
+                model.push(step);
+                (queue.snoc(step), model)
+            };
+            versions.push(next);
+        }
+        for (queue, model) in &versions {
+            assert_eq!(elems(queue), *model);
+            assert_eq!(queue.len(), model.len());
+            assert_eq!(queue.head(), model.first());
+        }
+    }
+
+    // Like `matches_a_vec`, but each step starts from some earlier version
+    // picked pseudo-randomly, so rotations get resumed from the middle and
+    // run more than once.
+    #[test]
+    fn reused_versions_match_a_vec() {
+        let mut versions = vec![(List::new(), Vec::new())];
+        let mut seed: usize = 1;
+        for step in 0..2000 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let pick = (seed >> 33) % versions.len();
+            // mostly recent versions, so the queues get long
+            let pick = if step % 4 == 0 {
+                pick
+            } else {
+                versions.len() - 1
+            };
+            let (queue, mut model) = versions[pick].clone();
+            let next = if (seed >> 20) % 3 == 0 {
+                if !model.is_empty() {
+                    model.remove(0);
+                }
+                (queue.tail(), model)
+            } else {
+                model.push(step);
+                (queue.snoc(step), model)
+            };
+            versions.push(next);
+        }
+        for (queue, model) in &versions {
+            assert_eq!(elems(queue), *model);
+            assert_eq!(queue.len(), model.len());
+            assert_eq!(queue.head(), model.first());
+        }
+    }
+
+    thread_local! {
+        static CLONES: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn clones() -> usize {
+        CLONES.with(|c| c.replace(0))
+    }
+
+    // Counts how many times it's been cloned, which is how many elements
+    // the queue copied into new nodes.
+    #[derive(Debug, PartialEq)]
+    struct CountsClones(usize);
+
+    impl Clone for CountsClones {
+        fn clone(&self) -> Self {
+            CLONES.with(|c| c.set(c.get() + 1));
+            CountsClones(self.0)
+        }
+    }
+
+    // Whichever version `snoc` or `tail` is called on, and however often,
+    // it only ever copies a couple of elements: a rotation is never done all
+    // at once.
+    #[test]
+    fn every_version_is_constant_time() {
+        let mut versions = vec![List::new()];
+        for i in 0..1000 {
+            let queue = versions.last().unwrap().snoc(CountsClones(i));
+            versions.push(if i % 3 == 2 { queue.tail() } else { queue });
+        }
+        clones();
+
+        for queue in &versions {
+            for _ in 0..3 {
+                drop(queue.snoc(CountsClones(0)));
+                assert!(clones() <= 4);
+                drop(queue.tail());
+                assert!(clones() <= 4);
+            }
+        }
+    }
+
+    #[test]
+    fn std_traits() {
+        let queue: List<i32> = (1..=3).collect();
+        assert_eq!(format!("{:?}", queue), "[1, 2, 3]");
+
+        // the same elements, split differently between front and rear
+        let other = List::new().snoc(0).snoc(1).tail().snoc(2).snoc(3);
+        assert_eq!(queue, other);
+        assert_ne!(queue, other.snoc(4));
+
+        let mut total = 0;
+        for elem in &queue {
+            total += elem;
+        }
+        assert_eq!(total, 6);
+    }
+}
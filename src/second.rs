@@ -1,7 +1,15 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::mem;
+
+use crate::traits::{Collection, Stack};
+
 // final implementation
-#[derive(Debug)]
 pub struct List<T> {
     head: Link<T>,
+    len: usize,
 }
 
 type Link<T> = Option<Box<Node<T>>>;
@@ -14,7 +22,7 @@ struct Node<T> {
 
 impl<T> List<T> {
     pub fn new() -> Self {
-        List { head: None }
+        List { head: None, len: 0 }
     }
 
     pub fn push(&mut self, elem: T) {
@@ -24,11 +32,13 @@ impl<T> List<T> {
         });
 
         self.head = Some(new_node);
+        self.len += 1;
     }
 
     pub fn pop(&mut self) -> Option<T> {
         self.head.take().map(|node| {
             self.head = node.next;
+            self.len -= 1;
             node.elem
         })
     }
@@ -47,6 +57,45 @@ impl<T> List<T> {
     pub fn peek_mut(&mut self) -> Option<&mut T> {
         self.head.as_mut().map(|node| &mut node.elem)
     }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Collection<T> for List<T> {
+    type Ref<'a>
+        = &'a T
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Stack<T> for List<T> {
+    fn push(&mut self, elem: T) {
+        self.push(elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek()
+    }
 }
 
 impl<T> Drop for List<T> {
@@ -61,8 +110,11 @@ impl<T> Drop for List<T> {
 // this is a tuple struct, just wraps around List<T>
 pub struct IntoIter<T>(List<T>);
 
-impl<T> List<T> {
-    pub fn into_iter(self) -> IntoIter<T> {
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
         IntoIter(self)
     }
 }
@@ -73,8 +125,14 @@ impl<T> Iterator for IntoIter<T> {
         // access fields of a tuple struct numerically
         self.0.pop()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
 }
 
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
 // above is not technically required, but nice to have
 // impl<T> Iterator for List<T> {
 //     type Item = T;
@@ -86,6 +144,7 @@ impl<T> Iterator for IntoIter<T> {
 // Iter is generic over *some* lifetime, it doesn't care
 pub struct Iter<'a, T> {
     next: Option<&'a Node<T>>,
+    len: usize,
 }
 
 // No lifetime here, List doesn't have any associated lifetimes
@@ -93,12 +152,13 @@ impl<T> List<T> {
     // We declare a fresh lifetime here for the *exact* borrow that
     // creates the iter. Now &self needs to be valid as long as the
     // Iter is around.
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
             // next: self.head.map(|node| &node),
             // next: self.head.map(|node| &*node),
             // next: self.head.as_ref().map(|node| &*node),
             next: self.head.as_deref(),
+            len: self.len,
         }
     }
 }
@@ -116,11 +176,18 @@ impl<'a, T> Iterator for Iter<'a, T> {
             // self.next = node.next.map(|node| &*node);
             // self.next = node.next.as_ref().map(|node| &*node);
             self.next = node.next.as_deref();
+            self.len -= 1;
             &node.elem
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
 // lifetime elision
 // // Only one reference in input, so the output must be derived from that input
 // fn foo(&A) -> &B; // sugar for:
@@ -136,12 +203,14 @@ impl<'a, T> Iterator for Iter<'a, T> {
 
 pub struct IterMut<'a, T> {
     next: Option<&'a mut Node<T>>,
+    len: usize,
 }
 
 impl<T> List<T> {
     pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut {
             next: self.head.as_deref_mut(),
+            len: self.len,
         }
     }
 }
@@ -153,14 +222,362 @@ impl<'a, T> Iterator for IterMut<'a, T> {
         // need to take the Option<&> here, whereas before it was copied over in Iter above
         self.next.take().map(|node| {
             self.next = node.next.as_deref_mut();
+            self.len -= 1;
             &mut node.elem
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+// A cursor sits *between* two elements, like a text cursor. "next" is the
+// element right after it, which for a singly-linked list is the only one we
+// can reach in O(1). A fresh cursor sits before the first element.
+pub struct Cursor<'a, T> {
+    next: &'a Link<T>,
+}
+
+impl<T> List<T> {
+    pub fn cursor(&self) -> Cursor<'_, T> {
+        Cursor { next: &self.head }
+    }
+}
+
+impl<'a, T> Cursor<'a, T> {
+    pub fn peek_next(&self) -> Option<&'a T> {
+        self.next.as_ref().map(|node| &node.elem)
+    }
+
+    // Steps over the next element; false (and no move) if there isn't one.
+    pub fn move_next(&mut self) -> bool {
+        match self.next {
+            Some(node) => {
+                self.next = &node.next;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// The cursor owns the `&mut` to the link after it, and that link is exactly
+// what every edit needs to rewrite, so they're all O(1). The Option is only
+// there so `move_next` can move the reference out of `self`; it's always Some.
+// `index` counts the elements before the cursor, so splitting knows how long
+// each half is without walking it.
+pub struct CursorMut<'a, T> {
+    next: Option<&'a mut Link<T>>,
+    len: &'a mut usize,
+    index: usize,
+}
+
+impl<T> List<T> {
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            next: Some(&mut self.head),
+            len: &mut self.len,
+            index: 0,
+        }
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    fn link(&mut self) -> &mut Link<T> {
+        self.next.as_mut().unwrap()
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        self.link().as_mut().map(|node| &mut node.elem)
+    }
+
+    // Steps over the next element; false (and no move) if there isn't one.
+    pub fn move_next(&mut self) -> bool {
+        let link = self.next.take().unwrap();
+        // Matching on `link` and putting it back in the None arm trips up the
+        // borrow checker, so check first and then commit to the move.
+        if link.is_none() {
+            self.next = Some(link);
+            return false;
+        }
+        self.next = link.as_mut().map(|node| &mut node.next);
+        self.index += 1;
+        true
+    }
+
+    // The new element goes right after the cursor, so it's the new `peek_next`.
+    pub fn insert_after(&mut self, elem: T) {
+        let link = self.link();
+        *link = Some(Box::new(Node {
+            elem,
+            next: link.take(),
+        }));
+        *self.len += 1;
+    }
+
+    pub fn remove_next(&mut self) -> Option<T> {
+        let link = self.link();
+        let elem = link.take().map(|node| {
+            *link = node.next;
+            node.elem
+        });
+        if elem.is_some() {
+            *self.len -= 1;
+        }
+        elem
+    }
+
+    // Everything after the cursor becomes its own list; the cursor is left at
+    // the end of what remains.
+    pub fn split_after(&mut self) -> List<T> {
+        let rest = List {
+            head: self.link().take(),
+            len: *self.len - self.index,
+        };
+        *self.len = self.index;
+        rest
+    }
+}
+
+// Bottom-up merge sort that only ever relinks the existing boxes. `runs[i]`
+// is either empty or a sorted run of 2^i nodes, like the digits of a binary
+// counter: each node taken off the front is "added" by merging it up through
+// the full slots. That needs O(log n) slots and no recursion at all.
+impl<T> List<T> {
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp)
+    }
+
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)))
+    }
+
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut sort = Sort {
+            list: self,
+            runs: Vec::new(),
+            carry: List::new(),
+        };
+        while let Some(mut node) = sort.list.head.take() {
+            sort.list.head = node.next.take();
+            sort.list.len -= 1;
+            sort.carry.head = Some(node);
+            sort.carry.len = 1;
+            let mut i = 0;
+            while i < sort.runs.len() && sort.runs[i].head.is_some() {
+                // runs[i] holds elements from earlier in the list, so it goes
+                // on the left to keep the sort stable
+                merge(&mut sort.runs[i], &mut sort.carry, &mut compare);
+                sort.carry = mem::take(&mut sort.runs[i]);
+                i += 1;
+            }
+            let carry = mem::take(&mut sort.carry);
+            if i == sort.runs.len() {
+                sort.runs.push(carry);
+            } else {
+                sort.runs[i] = carry;
+            }
+        }
+
+        // Higher slots hold earlier elements, so fold from the bottom up
+        for i in 0..sort.runs.len() {
+            merge(&mut sort.runs[i], &mut sort.carry, &mut compare);
+            sort.carry = mem::take(&mut sort.runs[i]);
+        }
+        mem::swap(sort.list, &mut sort.carry);
+    }
+}
+
+// Every node of a list being sorted is in the list, a run or the carry, and
+// if `compare` panics this puts the runs and the carry back in the list on
+// the way out. The list keeps all its elements, just not in any useful order.
+struct Sort<'a, T> {
+    list: &'a mut List<T>,
+    runs: Vec<List<T>>,
+    carry: List<T>,
+}
+
+impl<T> Drop for Sort<'_, T> {
+    fn drop(&mut self) {
+        for run in self.runs.iter_mut().chain(Some(&mut self.carry)) {
+            // In front of the list, so only the run has to be walked
+            if run.head.is_some() {
+                run.append(self.list);
+                mem::swap(self.list, run);
+            }
+        }
+    }
+}
+
+// Merges `right` into `left`. Stable: on ties the node from `left` goes
+// first. Nodes move over one at a time, so each is always in one list or the
+// other, even if `compare` panics halfway.
+fn merge<T, F>(left: &mut List<T>, right: &mut List<T>, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut cur = &mut left.head;
+    while let (Some(l), Some(r)) = (cur.as_ref(), right.head.as_ref()) {
+        if compare(&l.elem, &r.elem) == Ordering::Greater {
+            let mut node = right.head.take().unwrap();
+            right.head = node.next.take();
+            right.len -= 1;
+            node.next = cur.take();
+            *cur = Some(node);
+            left.len += 1;
+        }
+        cur = &mut cur.as_mut().unwrap().next;
+    }
+    if cur.is_none() {
+        *cur = right.head.take();
+        left.len += mem::take(&mut right.len);
+    }
+}
+
+// Bulk operations that only rewire `next` links; no element is moved or
+// reallocated.
+impl<T> List<T> {
+    pub fn reverse(&mut self) {
+        let mut reversed = None;
+        let mut cur_link = self.head.take();
+        while let Some(mut node) = cur_link {
+            cur_link = mem::replace(&mut node.next, reversed);
+            reversed = Some(node);
+        }
+        self.head = reversed;
+    }
+
+    // Moves all of `other`'s nodes to the bottom of this stack, leaving
+    // `other` empty. O(len of self), since we have to find our last node.
+    pub fn append(&mut self, other: &mut List<T>) {
+        let mut tail = &mut self.head;
+        while tail.is_some() {
+            tail = &mut tail.as_mut().unwrap().next;
+        }
+        *tail = other.head.take();
+        self.len += mem::take(&mut other.len);
+    }
+
+    // Splits off everything from index `at` down. Panics if `at > len`.
+    pub fn split_off(&mut self, at: usize) -> List<T> {
+        let mut cursor = self.cursor_mut();
+        for _ in 0..at {
+            assert!(cursor.move_next(), "split_off index out of bounds");
+        }
+        cursor.split_after()
+    }
+}
+
+// Clone walks the list once, appending at a tail link so the copy comes out
+// in the same order rather than reversed.
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        let mut list = List::new();
+        let mut tail = &mut list.head;
+        for elem in self {
+            tail = &mut tail
+                .insert(Box::new(Node {
+                    elem: elem.clone(),
+                    next: None,
+                }))
+                .next;
+        }
+        list.len = self.len;
+        list
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for List<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elem in self {
+            elem.hash(state);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut List<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+// Extending a stack pushes each element in turn, so the last one ends up on
+// top; collecting `[1, 2, 3]` iterates back out as `3, 2, 1`.
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::List;
+    use crate::test_util::hash;
+    use std::panic::{self, AssertUnwindSafe};
+
+    crate::stack_conformance!(conformance, crate::stack::List<i32>);
 
     #[test]
     fn basics() {
@@ -209,6 +626,7 @@ mod test {
         // Instead, it creates a pattern that will be matched against the argument to the closure;
         // |&mut value| means "the argument is a mutable reference, but just copy the value it points to into value, please."
         // If we just use |value|, the type of value will be &mut i32 and we can actually mutate the head:
+        #[allow(clippy::option_map_unit_fn)]
         list.peek_mut().map(|value| *value = 42);
 
         assert_eq!(list.peek(), Some(&42));
@@ -254,4 +672,300 @@ mod test {
         assert_eq!(iter.next(), Some(&mut 2));
         assert_eq!(iter.next(), Some(&mut 1));
     }
+
+    #[test]
+    fn cursor() {
+        let mut list = List::new();
+        list.push(1);
+        list.push(2);
+        list.push(3);
+
+        let mut cursor = list.cursor();
+        assert_eq!(cursor.peek_next(), Some(&3));
+        assert!(cursor.move_next());
+        assert_eq!(cursor.peek_next(), Some(&2));
+        assert!(cursor.move_next());
+        assert!(cursor.move_next());
+        assert_eq!(cursor.peek_next(), None);
+        assert!(!cursor.move_next());
+
+        let empty = List::<i32>::new();
+        assert_eq!(empty.cursor().peek_next(), None);
+    }
+
+    #[test]
+    fn cursor_mut() {
+        let mut list = List::new();
+        list.push(1);
+        list.push(3);
+
+        let mut cursor = list.cursor_mut();
+        assert_eq!(cursor.peek_next(), Some(&mut 3));
+        cursor.insert_after(4);
+        assert_eq!(cursor.peek_next(), Some(&mut 4));
+        assert!(cursor.move_next());
+        assert!(cursor.move_next());
+        cursor.insert_after(2);
+        if let Some(value) = cursor.peek_next() {
+            *value *= 10;
+        }
+        assert!(cursor.move_next());
+        assert!(cursor.move_next());
+        assert!(!cursor.move_next());
+        cursor.insert_after(0);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&4, &3, &20, &1, &0]);
+
+        let mut cursor = list.cursor_mut();
+        assert_eq!(cursor.remove_next(), Some(4));
+        assert!(cursor.move_next());
+        assert_eq!(cursor.remove_next(), Some(20));
+        assert!(cursor.move_next());
+        assert!(cursor.move_next());
+        assert_eq!(cursor.remove_next(), None);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&3, &1, &0]);
+
+        // Check the list is still a working stack after the edits
+        list.push(5);
+        assert_eq!(list.pop(), Some(5));
+        assert_eq!(list.pop(), Some(3));
+    }
+
+    #[test]
+    fn split_after() {
+        let mut list = List::new();
+        list.push(1);
+        list.push(2);
+        list.push(3);
+
+        let mut cursor = list.cursor_mut();
+        cursor.move_next();
+        let mut rest = cursor.split_after();
+        assert_eq!(cursor.peek_next(), None);
+        assert!(!cursor.move_next());
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&3]);
+        assert_eq!(rest.pop(), Some(2));
+        assert_eq!(rest.pop(), Some(1));
+        assert_eq!(rest.pop(), None);
+
+        // Splitting before the first element takes everything
+        let rest = list.cursor_mut().split_after();
+        assert_eq!(list.peek(), None);
+        assert_eq!(rest.iter().collect::<Vec<_>>(), vec![&3]);
+    }
+
+    #[test]
+    fn sort() {
+        let mut list = List::new();
+        list.sort();
+        assert_eq!(list.peek(), None);
+
+        for elem in [5, 1, 4, 1, 5, 9, 2, 6, 5, 3].iter() {
+            list.push(*elem);
+        }
+        list.sort();
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![1, 1, 2, 3, 4, 5, 5, 5, 6, 9]
+        );
+
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![9, 6, 5, 5, 5, 4, 3, 2, 1, 1]
+        );
+    }
+
+    #[test]
+    fn sort_is_stable() {
+        let mut list = List::new();
+        for (i, key) in [3, 1, 2, 1, 3, 2, 1].iter().enumerate() {
+            list.push((*key, i));
+        }
+        list.sort_by_key(|&(key, _)| key);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![(1, 6), (1, 3), (1, 1), (2, 5), (2, 2), (3, 4), (3, 0)]
+        );
+    }
+
+    // A `compare` that panics partway leaves the list in no particular
+    // order, but with every element still in it and `len` still right.
+    #[test]
+    fn sort_keeps_elements_when_compare_panics() {
+        for stop in (1..700).step_by(37) {
+            let mut list = List::new();
+            for i in 0..100 {
+                list.push((i * 37) % 100);
+            }
+            let mut calls = 0;
+            let sorted = panic::catch_unwind(AssertUnwindSafe(|| {
+                list.sort_by(|a, b| {
+                    calls += 1;
+                    assert!(calls != stop, "compare gave up");
+                    a.cmp(b)
+                })
+            }));
+            let mut elems: Vec<_> = list.iter().copied().collect();
+            assert_eq!(list.len(), elems.len());
+            if sorted.is_ok() {
+                assert!(elems.windows(2).all(|w| w[0] <= w[1]));
+            }
+            elems.sort_unstable();
+            assert_eq!(elems, (0..100).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn sort_long() {
+        // long enough to blow the stack if anything recursed per element
+        let mut list = List::new();
+        for i in 0..200_000 {
+            list.push((i * 7919) % 200_000);
+        }
+        list.sort();
+        assert!(list.iter().zip(0..).all(|(&elem, i)| elem == i));
+    }
+
+    #[test]
+    fn reverse() {
+        let mut list = List::<i32>::new();
+        list.reverse();
+        assert_eq!(list.peek(), None);
+
+        list.push(1);
+        list.reverse();
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1]);
+
+        list.push(2);
+        list.push(3);
+        let first: *const i32 = list.peek().unwrap();
+        list.reverse();
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+        // the old top is now at the bottom, in the very same node
+        assert_eq!(list.iter().last().unwrap() as *const i32, first);
+    }
+
+    #[test]
+    fn append() {
+        let mut list = List::new();
+        let mut other = List::new();
+        list.append(&mut other);
+        assert_eq!(list.peek(), None);
+
+        other.push(2);
+        other.push(1);
+        list.append(&mut other);
+        assert_eq!(other.peek(), None);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2]);
+
+        other.push(4);
+        other.push(3);
+        list.append(&mut other);
+        list.append(&mut List::new());
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &4]);
+
+        // Check other is still usable after being drained
+        other.push(5);
+        assert_eq!(other.pop(), Some(5));
+    }
+
+    #[test]
+    fn split_off() {
+        let mut list = List::new();
+        list.push(3);
+        list.push(2);
+        list.push(1);
+
+        let rest = list.split_off(3);
+        assert_eq!(rest.peek(), None);
+
+        let rest = list.split_off(1);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1]);
+        assert_eq!(rest.iter().collect::<Vec<_>>(), vec![&2, &3]);
+
+        let rest = list.split_off(0);
+        assert_eq!(list.peek(), None);
+        assert_eq!(rest.iter().collect::<Vec<_>>(), vec![&1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn split_off_past_end() {
+        let mut list = List::new();
+        list.push(1);
+        list.split_off(2);
+    }
+
+    #[test]
+    fn std_traits() {
+        let list: List<i32> = vec![1, 2, 3].into_iter().collect();
+        assert_eq!(format!("{:?}", list), "[3, 2, 1]");
+
+        let mut copy = list.clone();
+        assert_eq!(copy, list);
+        assert_eq!(hash(&copy), hash(&list));
+        assert_eq!(copy.cmp(&list), std::cmp::Ordering::Equal);
+
+        copy.push(0);
+        assert_ne!(copy, list);
+        assert!(copy < list);
+        copy.extend(vec![9]);
+        assert!(copy > list);
+        assert!(List::new() < list);
+        assert_eq!(List::<i32>::default(), List::new());
+
+        for elem in &mut copy {
+            *elem *= 2;
+        }
+        let mut elems = Vec::new();
+        for elem in &copy {
+            elems.push(*elem);
+        }
+        assert_eq!(elems, vec![18, 0, 6, 4, 2]);
+    }
+
+    #[test]
+    fn len() {
+        let mut list = List::new();
+        assert_eq!(list.len(), 0);
+        assert!(list.is_empty());
+
+        list.extend(vec![1, 2, 3]);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().len(), 3);
+        assert_eq!(list.iter_mut().len(), 3);
+        let mut iter = list.iter();
+        iter.next();
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+
+        list.pop();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.clone().len(), 2);
+
+        // Every bulk operation has to keep the count right too
+        let mut cursor = list.cursor_mut();
+        cursor.insert_after(4);
+        cursor.move_next();
+        assert_eq!(cursor.remove_next(), Some(2));
+        assert_eq!(list.len(), 2);
+
+        let mut other: List<i32> = vec![5, 6, 7].into_iter().collect();
+        list.append(&mut other);
+        assert_eq!(list.len(), 5);
+        assert_eq!(other.len(), 0);
+
+        list.sort();
+        assert_eq!(list.len(), 5);
+        list.reverse();
+        assert_eq!(list.len(), 5);
+
+        let rest = list.split_off(2);
+        assert_eq!(list.len(), 2);
+        assert_eq!(rest.len(), 3);
+        assert_eq!(rest.iter().len(), rest.iter().count());
+
+        let mut iter = rest.into_iter();
+        iter.next();
+        assert_eq!(iter.len(), 2);
+    }
 }
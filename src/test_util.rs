@@ -0,0 +1,135 @@
+// Odds and ends shared by the lists' unit tests.
+
+#[cfg(any(
+    feature = "arc_persistent",
+    feature = "concurrent_queue",
+    feature = "concurrent_stack",
+    feature = "rc_deque"
+))]
+pub(crate) use self::tracked::Drops;
+
+#[cfg(any(feature = "concurrent_queue", feature = "concurrent_stack"))]
+pub(crate) use self::stuck::Stuck;
+
+// Hashes `t` on its own, so tests can check that equal lists hash alike.
+#[cfg(any(
+    feature = "linked_deque",
+    feature = "persistent",
+    feature = "queue",
+    feature = "rc_deque",
+    feature = "stack"
+))]
+pub(crate) fn hash<T: std::hash::Hash>(t: &T) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::Hasher;
+
+    let mut hasher = DefaultHasher::new();
+    t.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(any(
+    feature = "arc_persistent",
+    feature = "concurrent_queue",
+    feature = "concurrent_stack",
+    feature = "rc_deque"
+))]
+mod tracked {
+    use std::sync::atomic::{AtomicUsize, Ordering::*};
+    use std::sync::Arc;
+
+    // Keeps count of the `Tracked` values made from it: how many have been
+    // dropped, and so how many are still alive. The counts are atomic so the
+    // concurrent lists can drop elements on whichever thread they like.
+    #[derive(Clone, Default)]
+    pub(crate) struct Drops(Arc<Counts>);
+
+    #[derive(Default)]
+    struct Counts {
+        made: AtomicUsize,
+        dropped: AtomicUsize,
+    }
+
+    // An element that reports its drop to the `Drops` it came from.
+    pub(crate) struct Tracked(Drops);
+
+    impl Drops {
+        pub(crate) fn new() -> Self {
+            Self::default()
+        }
+
+        pub(crate) fn track(&self) -> Tracked {
+            self.0.made.fetch_add(1, SeqCst);
+            Tracked(self.clone())
+        }
+
+        pub(crate) fn dropped(&self) -> usize {
+            self.0.dropped.load(SeqCst)
+        }
+
+        pub(crate) fn live(&self) -> usize {
+            self.0.made.load(SeqCst) - self.dropped()
+        }
+    }
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            (self.0).0.dropped.fetch_add(1, SeqCst);
+        }
+    }
+}
+
+#[cfg(any(feature = "concurrent_queue", feature = "concurrent_stack"))]
+mod stuck {
+    use std::sync::atomic::{AtomicBool, Ordering::*};
+    use std::sync::{Arc, Barrier};
+
+    // An element whose first clone stops halfway through, so a test can do
+    // something while, say, a `peek` is in the middle of cloning it. The
+    // test calls `wait_halfway` to know the clone has got there, and
+    // `release` to let it finish.
+    pub(crate) struct Stuck {
+        halfway: Arc<Barrier>,
+        stopped: Arc<AtomicBool>,
+    }
+
+    impl Stuck {
+        pub(crate) fn new() -> Self {
+            Stuck {
+                halfway: Arc::new(Barrier::new(2)),
+                stopped: Arc::new(AtomicBool::new(false)),
+            }
+        }
+
+        // Another handle on the same element, without counting as a clone.
+        pub(crate) fn share(&self) -> Self {
+            Stuck {
+                halfway: self.halfway.clone(),
+                stopped: self.stopped.clone(),
+            }
+        }
+
+        pub(crate) fn wait_halfway(&self) {
+            self.halfway.wait();
+        }
+
+        pub(crate) fn release(&self) {
+            self.halfway.wait();
+        }
+
+        // How many handles and clones are still alive, this one included.
+        pub(crate) fn alive(&self) -> usize {
+            Arc::strong_count(&self.halfway)
+        }
+    }
+
+    impl Clone for Stuck {
+        fn clone(&self) -> Self {
+            if !self.stopped.swap(true, SeqCst) {
+                self.halfway.wait();
+                self.halfway.wait();
+            }
+            self.share()
+        }
+    }
+}
@@ -1,82 +1,436 @@
 use std::rc::Rc;
 
-pub struct List<T> {
-    head: Link<T>,
-}
+// The list is written once, as a macro over the pointer its nodes are shared
+// through, and stamped out twice: here with `Rc`, and in `arc_persistent` with `Arc`
+// so that versions can be handed to other threads. `$Ptr` just has to have
+// `Rc`'s `new`, `ptr_eq`, `try_unwrap` and `into_inner`.
+macro_rules! persistent_list {
+    ($Ptr:ident) => {
+        use std::cmp::Ordering;
+        use std::fmt;
+        use std::hash::{Hash, Hasher};
+        use std::iter::FromIterator;
 
-type Link<T> = Option<Rc<Node<T>>>;
+        use $crate::traits::{Collection, Stack};
 
-struct Node<T> {
-    elem: T,
-    next: Link<T>,
-}
+        pub struct List<T> {
+            head: Link<T>,
+        }
 
-impl<T> List<T> {
-    pub fn new() -> Self {
-        List { head: None }
-    }
+        type Link<T> = Option<$Ptr<Node<T>>>;
 
-    pub fn append(&self, elem: T) -> List<T> {
-        List {
-            head: Some(Rc::new(Node {
-                elem,
-                next: self.head.clone(),
-            })),
+        struct Node<T> {
+            elem: T,
+            next: Link<T>,
         }
-    }
 
-    pub fn tail(&self) -> List<T> {
-        // cdr
-        List {
-            // head: self.head.as_ref().map(|node| node.next.clone()),
-            head: self.head.as_ref().and_then(|node| node.next.clone()),
+        impl<T> List<T> {
+            pub fn new() -> Self {
+                List { head: None }
+            }
+
+            pub fn append(&self, elem: T) -> List<T> {
+                List {
+                    head: Some($Ptr::new(Node {
+                        elem,
+                        next: self.head.clone(),
+                    })),
+                }
+            }
+
+            pub fn tail(&self) -> List<T> {
+                // cdr
+                List {
+                    // head: self.head.as_ref().map(|node| node.next.clone()),
+                    head: self.head.as_ref().and_then(|node| node.next.clone()),
+                }
+            }
+
+            pub fn head(&self) -> Option<&T> {
+                self.head.as_ref().map(|node| &node.elem)
+            }
+
+            // Puts `elems` in front of `rest`, first element first, sharing
+            // `rest` as it is. Everything below builds its new prefix this
+            // way.
+            fn prepend_all(elems: Vec<T>, rest: Link<T>) -> List<T> {
+                let mut head = rest;
+                for elem in elems.into_iter().rev() {
+                    head = Some($Ptr::new(Node { elem, next: head }));
+                }
+                List { head }
+            }
         }
-    }
 
-    pub fn head(&self) -> Option<&T> {
-        self.head.as_ref().map(|node| &node.elem)
-    }
-}
+        // Combinators. Each returns a new list and leaves `self` alone, and
+        // shares as much of the existing nodes as it can: only the part of the
+        // result that differs from some suffix of an input gets new nodes.
+        impl<T> List<T> {
+            // The list without its first `n` elements. Shares everything,
+            // allocates nothing.
+            pub fn drop(&self, n: usize) -> List<T> {
+                let mut link = &self.head;
+                for _ in 0..n {
+                    match link {
+                        Some(node) => link = &node.next,
+                        None => break,
+                    }
+                }
+                List { head: link.clone() }
+            }
 
-pub struct Iter<'a, T> {
-    next: Option<&'a Node<T>>,
-}
+            pub fn fold<B, F: FnMut(B, &T) -> B>(&self, init: B, f: F) -> B {
+                self.iter().fold(init, f)
+            }
+
+            // The longest tail the two lists share node for node (not just
+            // element for element); empty if they share nothing. Lists can
+            // only share a suffix, so after skipping the longer one's extra
+            // elements the first common node is where it starts. O(len) and
+            // allocates nothing.
+            pub fn common_suffix(&self, other: &List<T>) -> List<T> {
+                let (len, other_len) = (self.len(), other.len());
+                let mut a = &self.drop(len.saturating_sub(other_len)).head;
+                let mut b = &other.drop(other_len.saturating_sub(len)).head;
+                loop {
+                    match (a, b) {
+                        (Some(x), Some(y)) if !$Ptr::ptr_eq(x, y) => {
+                            a = &x.next;
+                            b = &y.next;
+                        }
+                        _ => return List { head: a.clone() },
+                    }
+                }
+            }
 
-impl<T> List<T> {
-    pub fn iter(&self) -> Iter<'_, T> {
-        Iter {
-            next: self.head.as_deref(),
+            // Every node is new; a list of `U`s can't share with a list of
+            // `T`s.
+            pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> List<U> {
+                List::prepend_all(self.iter().map(f).collect(), None)
+            }
         }
-    }
-}
 
-impl<'a, T> Iterator for Iter<'a, T> {
-    type Item = &'a T;
+        impl<T: Clone> List<T> {
+            // The first `n` elements. New nodes, unless `n` covers the whole
+            // list, in which case it's the list itself.
+            pub fn take(&self, n: usize) -> List<T> {
+                if self.drop(n).head.is_none() {
+                    return self.clone();
+                }
+                List::prepend_all(self.iter().take(n).cloned().collect(), None)
+            }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.next.map(|node| {
-            self.next = node.next.as_deref();
-            &node.elem
-        })
-    }
-}
+            // Shares the longest suffix that `f` keeps whole; only the
+            // elements before the last one it rejects are copied.
+            pub fn filter<F: FnMut(&T) -> bool>(&self, mut f: F) -> List<T> {
+                let mut copied = Vec::new();
+                let mut since_rejected = Vec::new();
+                let mut shared = self.head.clone();
+                let mut link = &self.head;
+                while let Some(node) = link {
+                    if f(&node.elem) {
+                        since_rejected.push(&node.elem);
+                    } else {
+                        copied.extend(since_rejected.drain(..).cloned());
+                        shared = node.next.clone();
+                    }
+                    link = &node.next;
+                }
+                List::prepend_all(copied, shared)
+            }
+
+            // All new nodes; there's no suffix of the original that's also a
+            // suffix of the reversal (bar the empty one).
+            pub fn reverse(&self) -> List<T> {
+                // collecting prepends, so comes out reversed
+                self.iter().cloned().collect()
+            }
+
+            // `self` followed by `other`. Copies `self`, and shares all of
+            // `other`.
+            pub fn concat(&self, other: &List<T>) -> List<T> {
+                List::prepend_all(self.iter().cloned().collect(), other.head.clone())
+            }
 
-impl<T> Drop for List<T> {
-    fn drop(&mut self) {
-        let mut head = self.head.take();
-        while let Some(node) = head {
-            if let Ok(mut node) = Rc::try_unwrap(node) {
-                head = node.next.take();
-            } else {
-                break;
+            // The list with the element at `index` replaced. Like `insert` and
+            // `remove`, it copies the path to `index` and shares everything
+            // after, so it's O(index). Panics if `index` is out of bounds.
+            pub fn set(&self, index: usize, elem: T) -> List<T> {
+                match self.copy_prefix(index) {
+                    Some((prefix, Some(node))) => List::prepend_all(
+                        prefix,
+                        Some($Ptr::new(Node {
+                            elem,
+                            next: node.next.clone(),
+                        })),
+                    ),
+                    _ => panic!("set index out of bounds"),
+                }
+            }
+
+            // The list with `elem` inserted before whatever is at `index`.
+            // Panics if `index > len`.
+            pub fn insert(&self, index: usize, elem: T) -> List<T> {
+                let (prefix, rest) = self.copy_prefix(index).expect("insert index out of bounds");
+                List::prepend_all(
+                    prefix,
+                    Some($Ptr::new(Node {
+                        elem,
+                        next: rest.clone(),
+                    })),
+                )
+            }
+
+            // The list without the element at `index`. Panics if `index` is
+            // out of bounds.
+            pub fn remove(&self, index: usize) -> List<T> {
+                match self.copy_prefix(index) {
+                    Some((prefix, Some(node))) => List::prepend_all(prefix, node.next.clone()),
+                    _ => panic!("remove index out of bounds"),
+                }
+            }
+
+            // Copies of the first `index` elements, and the link that follows
+            // them; None if the list is shorter than that.
+            fn copy_prefix(&self, index: usize) -> Option<(Vec<T>, &Link<T>)> {
+                let mut prefix = Vec::with_capacity(index);
+                let mut link = &self.head;
+                for _ in 0..index {
+                    let node = link.as_ref()?;
+                    prefix.push(node.elem.clone());
+                    link = &node.next;
+                }
+                Some((prefix, link))
+            }
+
+            // Pairs up elements until either list runs out. All new nodes.
+            pub fn zip<U: Clone>(&self, other: &List<U>) -> List<(T, U)> {
+                let pairs = self.iter().cloned().zip(other.iter().cloned()).collect();
+                List::prepend_all(pairs, None)
             }
         }
-    }
+
+        impl<T> Default for List<T> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        pub struct Iter<'a, T> {
+            next: Option<&'a Node<T>>,
+        }
+
+        impl<T> List<T> {
+            pub fn iter(&self) -> Iter<'_, T> {
+                Iter {
+                    next: self.head.as_deref(),
+                }
+            }
+        }
+
+        impl<'a, T> Iterator for Iter<'a, T> {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.next.map(|node| {
+                    self.next = node.next.as_deref();
+                    &node.elem
+                })
+            }
+        }
+
+        impl<T> Collection<T> for List<T> {
+            type Ref<'a>
+                = &'a T
+            where
+                T: 'a;
+
+            fn len(&self) -> usize {
+                self.iter().count()
+            }
+        }
+
+        // Pushing and popping a persistent list just moves our own head
+        // around; other lists sharing the nodes never notice. `pop` can only
+        // move the element out if nobody else holds the node, otherwise it has
+        // to clone.
+        impl<T: Clone> Stack<T> for List<T> {
+            fn push(&mut self, elem: T) {
+                self.head = Some($Ptr::new(Node {
+                    elem,
+                    next: self.head.take(),
+                }));
+            }
+
+            fn pop(&mut self) -> Option<T> {
+                self.head.take().map(|node| match $Ptr::try_unwrap(node) {
+                    Ok(node) => {
+                        self.head = node.next;
+                        node.elem
+                    }
+                    Err(node) => {
+                        self.head = node.next.clone();
+                        node.elem.clone()
+                    }
+                })
+            }
+
+            fn peek(&self) -> Option<&T> {
+                self.head()
+            }
+        }
+
+        // Frees our nodes one at a time rather than recursively, and stops at
+        // the first one another list still holds. `into_inner` rather than
+        // `try_unwrap`: when two threads drop lists sharing a suffix, both
+        // could see the other's reference, both fail `try_unwrap`, and
+        // whichever let go of its `Arc` last would free the whole suffix
+        // through `Node`'s recursive drop. `into_inner` gives the node to
+        // exactly one of them, which carries on the loop.
+        impl<T> Drop for List<T> {
+            fn drop(&mut self) {
+                let mut head = self.head.take();
+                while let Some(node) = head {
+                    head = $Ptr::into_inner(node).and_then(|mut node| node.next.take());
+                }
+            }
+        }
+
+        // Cloning a persistent list is just sharing its head; no elements are
+        // copied, so this doesn't even need `T: Clone`.
+        impl<T> Clone for List<T> {
+            fn clone(&self) -> Self {
+                List {
+                    head: self.head.clone(),
+                }
+            }
+        }
+
+        impl<T: fmt::Debug> fmt::Debug for List<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self.iter()).finish()
+            }
+        }
+
+        // Versions of a list mostly share their tails, so comparisons walk
+        // both lists in step and stop as soon as they reach the same node:
+        // from there on the two are one and the same, and the rest compares
+        // equal without looking. See `PartialEq` below for what that means
+        // when `T` isn't `Eq`.
+        fn compare<T, F>(a: &List<T>, b: &List<T>, mut f: F) -> Option<Ordering>
+        where
+            F: FnMut(&T, &T) -> Option<Ordering>,
+        {
+            let (mut a, mut b) = (&a.head, &b.head);
+            loop {
+                match (a, b) {
+                    (None, None) => return Some(Ordering::Equal),
+                    (None, Some(_)) => return Some(Ordering::Less),
+                    (Some(_), None) => return Some(Ordering::Greater),
+                    (Some(x), Some(y)) => {
+                        if $Ptr::ptr_eq(x, y) {
+                            return Some(Ordering::Equal);
+                        }
+                        match f(&x.elem, &y.elem) {
+                            Some(Ordering::Equal) => {}
+                            unequal => return unequal,
+                        }
+                        a = &x.next;
+                        b = &y.next;
+                    }
+                }
+            }
+        }
+
+        /// Lists are equal when their elements are, front to back, except that
+        /// once both reach the same node (the same shared tail, at the same
+        /// position) the rest counts as equal without being compared.
+        ///
+        /// For `T: Eq` that only makes it faster. For a `T` whose values
+        /// needn't equal themselves, such as `f64` and its NaN, it makes the
+        /// answer depend on sharing: a list holding a NaN equals its clone,
+        /// and any version that shares the NaN's node, but not a list built
+        /// separately from the same values. `Rc` and `Arc` only compare by
+        /// pointer when `T: Eq`; without specialisation a list can't tell the
+        /// cases apart, so it always does.
+        impl<T: PartialEq> PartialEq for List<T> {
+            fn eq(&self, other: &Self) -> bool {
+                let eq = |a: &T, b: &T| if a == b { Some(Ordering::Equal) } else { None };
+                compare(self, other, eq) == Some(Ordering::Equal)
+            }
+        }
+
+        impl<T: Eq> Eq for List<T> {}
+
+        /// Lexicographic, with the same shortcut as `PartialEq`: elements in a
+        /// shared tail compare `Equal` to themselves, so a NaN there gives
+        /// `Some(Equal)` where comparing it would give `None`.
+        impl<T: PartialOrd> PartialOrd for List<T> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                compare(self, other, |a, b| a.partial_cmp(b))
+            }
+        }
+
+        impl<T: Ord> Ord for List<T> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                compare(self, other, |a, b| Some(a.cmp(b))).unwrap()
+            }
+        }
+
+        // Hashing has no such shortcut, since a `Hasher` can't reuse what it
+        // saw last time.
+        impl<T: Hash> Hash for List<T> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.len().hash(state);
+                for elem in self {
+                    elem.hash(state);
+                }
+            }
+        }
+
+        impl<T> FromIterator<T> for List<T> {
+            fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+                let mut list = List::new();
+                list.extend(iter);
+                list
+            }
+        }
+
+        impl<'a, T> IntoIterator for &'a List<T> {
+            type Item = &'a T;
+            type IntoIter = Iter<'a, T>;
+
+            fn into_iter(self) -> Iter<'a, T> {
+                self.iter()
+            }
+        }
+
+        // Same as the stack: each element goes on the front in turn. Nodes
+        // that were already there are untouched, so other lists sharing them
+        // don't notice.
+        impl<T> Extend<T> for List<T> {
+            fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+                for elem in iter {
+                    self.head = Some($Ptr::new(Node {
+                        elem,
+                        next: self.head.take(),
+                    }));
+                }
+            }
+        }
+    };
 }
 
+#[cfg(feature = "arc_persistent")]
+pub(crate) use persistent_list;
+
+persistent_list!(Rc);
+
 #[cfg(test)]
 mod test {
     use super::List;
+    use crate::test_util::hash;
+    use std::rc::Rc;
 
     #[test]
     fn basics() {
@@ -109,4 +463,276 @@ mod test {
         assert_eq!(iter.next(), Some(&2));
         assert_eq!(iter.next(), Some(&1));
     }
+
+    // Whether two lists start at the very same node.
+    fn same_head<T>(a: &List<T>, b: &List<T>) -> bool {
+        match (&a.head, &b.head) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    fn elems(list: &List<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    // Builds a list with its elements in the order given.
+    fn list(elems: &[i32]) -> List<i32> {
+        elems.iter().rev().copied().collect()
+    }
+
+    #[test]
+    fn drop_and_take() {
+        let all = list(&[1, 2, 3, 4]);
+
+        let rest = all.drop(2);
+        assert_eq!(elems(&rest), vec![3, 4]);
+        assert!(same_head(&rest, &all.tail().tail()));
+        assert!(same_head(&all.drop(0), &all));
+        assert!(all.drop(4).head().is_none());
+        assert!(all.drop(10).head().is_none());
+
+        let front = all.take(2);
+        assert_eq!(elems(&front), vec![1, 2]);
+        assert!(!same_head(&front, &all));
+        assert!(same_head(&all.take(4), &all));
+        assert!(same_head(&all.take(10), &all));
+        assert!(all.take(0).head().is_none());
+        assert_eq!(elems(&all), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn filter_shares_kept_suffix() {
+        let all = list(&[1, 2, 3, 4, 6, 8]);
+
+        let even = all.filter(|elem| elem % 2 == 0);
+        assert_eq!(elems(&even), vec![2, 4, 6, 8]);
+        // 3 was the last one dropped; everything after it is shared
+        assert!(same_head(&even.drop(1), &all.drop(3)));
+
+        assert!(same_head(&all.filter(|_| true), &all));
+        assert!(all.filter(|_| false).head().is_none());
+        let no_last = all.filter(|elem| *elem != 8);
+        assert_eq!(elems(&no_last), vec![1, 2, 3, 4, 6]);
+        assert_eq!(elems(&all), vec![1, 2, 3, 4, 6, 8]);
+    }
+
+    #[test]
+    fn concat_shares_right_operand() {
+        let left = list(&[1, 2]);
+        let right = list(&[3, 4]);
+
+        let both = left.concat(&right);
+        assert_eq!(elems(&both), vec![1, 2, 3, 4]);
+        assert!(same_head(&both.drop(2), &right));
+        assert!(same_head(&List::new().concat(&right), &right));
+        assert_eq!(elems(&right.concat(&List::new())), vec![3, 4]);
+        assert_eq!(elems(&left), vec![1, 2]);
+    }
+
+    #[test]
+    fn map_reverse_zip_fold() {
+        let all = list(&[1, 2, 3]);
+
+        assert_eq!(elems(&all.map(|elem| elem * 10)), vec![10, 20, 30]);
+        let strings = all.map(|elem| elem.to_string());
+        assert_eq!(strings.head().map(String::as_str), Some("1"));
+
+        assert_eq!(elems(&all.reverse()), vec![3, 2, 1]);
+        assert!(List::<i32>::new().reverse().head().is_none());
+
+        let pairs = all.zip(&list(&[4, 5]));
+        let pairs: Vec<_> = pairs.iter().copied().collect();
+        assert_eq!(pairs, vec![(1, 4), (2, 5)]);
+
+        assert_eq!(all.fold(0, |sum, elem| sum + elem), 6);
+        assert_eq!(
+            all.fold(Vec::new(), |mut v, elem| {
+                v.push(*elem);
+                v
+            }),
+            vec![1, 2, 3]
+        );
+        assert_eq!(elems(&all), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn set_insert_remove_share_suffix() {
+        let all = list(&[1, 2, 3, 4, 5]);
+
+        let set = all.set(1, 20);
+        assert_eq!(elems(&set), vec![1, 20, 3, 4, 5]);
+        // only the path up to the change is new
+        assert!(same_head(&set.drop(2), &all.drop(2)));
+        assert!(!same_head(&set.drop(1), &all.drop(1)));
+        assert!(!same_head(&set, &all));
+        let last = all.set(4, 50);
+        assert_eq!(elems(&last), vec![1, 2, 3, 4, 50]);
+        assert!(same_head(&all.set(0, 10).tail(), &all.tail()));
+
+        let inserted = all.insert(2, 0);
+        assert_eq!(elems(&inserted), vec![1, 2, 0, 3, 4, 5]);
+        assert!(same_head(&inserted.drop(3), &all.drop(2)));
+        assert!(same_head(&all.insert(0, 0).tail(), &all));
+        let appended = all.insert(5, 6);
+        assert_eq!(elems(&appended), vec![1, 2, 3, 4, 5, 6]);
+
+        let removed = all.remove(2);
+        assert_eq!(elems(&removed), vec![1, 2, 4, 5]);
+        assert!(same_head(&removed.drop(2), &all.drop(3)));
+        assert!(same_head(&all.remove(0), &all.tail()));
+        assert_eq!(elems(&all.remove(4)), vec![1, 2, 3, 4]);
+
+        // and the original never changed
+        assert_eq!(elems(&all), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "set index out of bounds")]
+    fn set_out_of_bounds() {
+        list(&[1, 2]).set(2, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "insert index out of bounds")]
+    fn insert_out_of_bounds() {
+        list(&[1, 2]).insert(3, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "remove index out of bounds")]
+    fn remove_out_of_bounds() {
+        List::<i32>::new().remove(0);
+    }
+
+    #[test]
+    fn common_suffix() {
+        let base = list(&[4, 5, 6]);
+        let a = base.append(3).append(2);
+        let b = base.append(9);
+        assert!(same_head(&a.common_suffix(&b), &base));
+        assert!(same_head(&b.common_suffix(&a), &base));
+        assert!(same_head(&a.common_suffix(&a), &a));
+        assert!(same_head(&a.common_suffix(&base), &base));
+
+        // equal elements aren't enough, the nodes have to be the same ones
+        let copy = list(&[2, 3, 4, 5, 6]);
+        assert_eq!(copy, a);
+        assert!(copy.common_suffix(&a).head().is_none());
+
+        // a path copy shares everything after the changed element
+        let edited = a.set(1, 30);
+        assert!(same_head(&edited.common_suffix(&a), &base));
+        assert_eq!(elems(&edited.common_suffix(&a.remove(4))), vec![]);
+        assert!(List::<i32>::new().common_suffix(&a).head().is_none());
+    }
+
+    // Compares like an i32, and counts how many times it was asked to.
+    #[derive(Clone, Debug)]
+    struct Counted(i32);
+
+    thread_local! {
+        static COMPARISONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    fn comparisons() -> usize {
+        COMPARISONS.with(|c| c.replace(0))
+    }
+
+    impl PartialEq for Counted {
+        fn eq(&self, other: &Self) -> bool {
+            COMPARISONS.with(|c| c.set(c.get() + 1));
+            self.0 == other.0
+        }
+    }
+
+    impl Eq for Counted {}
+
+    impl PartialOrd for Counted {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Counted {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            COMPARISONS.with(|c| c.set(c.get() + 1));
+            self.0.cmp(&other.0)
+        }
+    }
+
+    #[test]
+    fn comparisons_stop_at_shared_nodes() {
+        let history: List<Counted> = (0..1000).map(Counted).collect();
+        comparisons();
+
+        assert_eq!(history, history.clone());
+        assert_eq!(history.cmp(&history.clone()), std::cmp::Ordering::Equal);
+        assert_eq!(comparisons(), 0);
+
+        // an edit near the front only costs the elements before it
+        let edited = history.set(2, Counted(-1));
+        assert_ne!(history, edited);
+        assert_eq!(comparisons(), 3);
+        assert!(edited < history);
+        assert_eq!(comparisons(), 3);
+
+        let undone = edited.set(2, Counted(997));
+        assert_eq!(undone, history);
+        assert_eq!(undone.cmp(&history), std::cmp::Ordering::Equal);
+        assert_eq!(comparisons(), 6);
+
+        // with nothing shared every element is still looked at
+        let copy: List<Counted> = (0..1000).map(Counted).collect();
+        assert_eq!(copy, history);
+        assert_eq!(comparisons(), 1000);
+    }
+
+    // Pins down what the pointer shortcut does to a type that isn't `Eq`: a
+    // NaN equals itself in a shared node, and nowhere else.
+    #[test]
+    fn nan_compares_by_sharing() {
+        use std::cmp::Ordering::Equal;
+
+        let nan: List<f64> = vec![2.0, f64::NAN, 1.0].into_iter().collect();
+        assert_eq!(nan, nan.clone());
+        assert_eq!(nan.partial_cmp(&nan.clone()), Some(Equal));
+
+        // a path copy that stops before the NaN still shares it...
+        let edited = nan.set(0, 1.0);
+        assert_eq!(edited, nan);
+        // ...one that copies it doesn't
+        let copied = nan.set(2, 2.0);
+        assert_ne!(copied, nan);
+        assert_eq!(copied.partial_cmp(&nan), None);
+
+        let fresh: List<f64> = vec![2.0, f64::NAN, 1.0].into_iter().collect();
+        assert_ne!(fresh, nan);
+        assert_eq!(fresh.partial_cmp(&nan), None);
+    }
+
+    #[test]
+    fn std_traits() {
+        let list: List<i32> = vec![1, 2, 3].into_iter().collect();
+        assert_eq!(format!("{:?}", list), "[3, 2, 1]");
+
+        let mut copy = list.clone();
+        assert_eq!(copy, list);
+        assert_eq!(hash(&copy), hash(&list));
+        assert_eq!(copy.head().unwrap() as *const i32, list.head().unwrap());
+
+        copy.extend(vec![4]);
+        assert_eq!(format!("{:?}", copy), "[4, 3, 2, 1]");
+        assert_eq!(format!("{:?}", list), "[3, 2, 1]");
+        assert!(copy > list);
+        assert!(list.tail() < list);
+        assert_eq!(List::<i32>::default(), List::new());
+
+        let mut elems = Vec::new();
+        for elem in &copy {
+            elems.push(*elem);
+        }
+        assert_eq!(elems, vec![4, 3, 2, 1]);
+    }
 }
@@ -0,0 +1,164 @@
+use std::ops::Deref;
+
+/// What every list can answer about itself.
+///
+/// The lists don't agree on how they hand out references to their elements:
+/// most give back a plain `&T`, but `rc_deque` can only give back a `Ref<T>`
+/// guard. `Ref` lets every list pick whatever `Deref<Target = T>` it has.
+///
+/// Lists that implement `Hash` all do it the same way, like std's
+/// collections: the length first, then the elements front to back. The same
+/// elements hash alike whichever list holds them, and with the length in
+/// front, a list of lists can't collide with another just by moving an
+/// element from one inner list to the next.
+pub trait Collection<T> {
+    type Ref<'a>: Deref<Target = T>
+    where
+        Self: 'a;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Last in, first out.
+pub trait Stack<T>: Collection<T> {
+    fn push(&mut self, elem: T);
+
+    fn pop(&mut self) -> Option<T>;
+
+    fn peek(&self) -> Option<Self::Ref<'_>>;
+}
+
+/// First in, first out.
+///
+/// The method names differ from `Stack` on purpose, so a type that is both
+/// (every `Deque`) doesn't need UFCS for every call.
+pub trait Queue<T>: Collection<T> {
+    fn enqueue(&mut self, elem: T);
+
+    fn dequeue(&mut self) -> Option<T>;
+
+    fn front(&self) -> Option<Self::Ref<'_>>;
+}
+
+/// Push, pop and peek at both ends.
+pub trait Deque<T>: Stack<T> + Queue<T> {
+    fn push_front(&mut self, elem: T);
+
+    fn push_back(&mut self, elem: T);
+
+    fn pop_front(&mut self) -> Option<T>;
+
+    fn pop_back(&mut self) -> Option<T>;
+
+    fn peek_front(&self) -> Option<Self::Ref<'_>>;
+
+    fn peek_back(&self) -> Option<Self::Ref<'_>>;
+}
+
+// `stack`, `rc_deque` and `queue` run the full conformance suite from their
+// own modules; the lists without an owning iterator get the rest here.
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "int_stack")]
+    mod int_stack {
+        use crate::conformance::stack;
+        use crate::int_stack::List;
+
+        #[test]
+        fn stack() {
+            stack::lifo::<List>();
+            stack::exhaustion::<List>();
+            stack::reuse_after_exhaustion::<List>();
+            stack::peek_consistency::<List>();
+        }
+    }
+
+    #[cfg(feature = "persistent")]
+    mod persistent {
+        use crate::conformance::stack;
+        use crate::persistent::List;
+
+        #[test]
+        fn stack() {
+            stack::lifo::<List<i32>>();
+            stack::exhaustion::<List<i32>>();
+            stack::reuse_after_exhaustion::<List<i32>>();
+            stack::peek_consistency::<List<i32>>();
+        }
+    }
+
+    #[cfg(feature = "arc_persistent")]
+    mod arc_persistent {
+        use crate::arc_persistent::List;
+        use crate::conformance::stack;
+
+        #[test]
+        fn stack() {
+            stack::lifo::<List<i32>>();
+            stack::exhaustion::<List<i32>>();
+            stack::reuse_after_exhaustion::<List<i32>>();
+            stack::peek_consistency::<List<i32>>();
+        }
+    }
+
+    #[cfg(feature = "persistent_queue")]
+    mod persistent_queue {
+        use crate::conformance::queue;
+        use crate::persistent_queue::List;
+
+        #[test]
+        fn queue() {
+            queue::fifo::<List<i32>>();
+            queue::exhaustion::<List<i32>>();
+            queue::reuse_after_exhaustion::<List<i32>>();
+            queue::peek_consistency::<List<i32>>();
+        }
+    }
+
+    #[cfg(all(
+        feature = "arc_persistent",
+        feature = "linked_deque",
+        feature = "persistent",
+        feature = "queue",
+        feature = "rc_deque",
+        feature = "stack"
+    ))]
+    mod hash {
+        use crate::test_util::hash;
+        use crate::traits::{Queue, Stack};
+
+        // 1, 2, 3 from the front.
+        fn stacked<S: Stack<i32> + Default>() -> S {
+            let mut list = S::default();
+            for elem in (1..=3).rev() {
+                list.push(elem);
+            }
+            list
+        }
+
+        fn queued<Q: Queue<i32> + Default>() -> Q {
+            let mut list = Q::default();
+            for elem in 1..=3 {
+                list.enqueue(elem);
+            }
+            list
+        }
+
+        #[test]
+        fn lists_hash_alike() {
+            let expected = hash(&stacked::<crate::stack::List<i32>>());
+            assert_eq!(hash(&stacked::<crate::persistent::List<i32>>()), expected);
+            assert_eq!(
+                hash(&stacked::<crate::arc_persistent::List<i32>>()),
+                expected
+            );
+            assert_eq!(hash(&queued::<crate::queue::List<i32>>()), expected);
+            assert_eq!(hash(&queued::<crate::rc_deque::List<i32>>()), expected);
+            assert_eq!(hash(&queued::<crate::linked_deque::List<i32>>()), expected);
+        }
+    }
+}
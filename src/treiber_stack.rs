@@ -0,0 +1,388 @@
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering::*};
+use std::sync::OnceLock;
+
+use crate::hazard::Domain;
+
+// A Treiber stack: the same singly-linked stack as `second`, except `head`
+// is an atomic pointer swung with compare-and-swap, so every operation takes
+// `&self` and the list can be shared between threads without a lock.
+//
+// Nothing ever waits: a thread that loses a CAS only ever loses it to one
+// that won. The one place that could wait is a pop that finds a `peek` still
+// cloning the element it just popped. Instead, the pop clones the element as
+// well and leaves the original in the node, which drops it when the hazard
+// domain frees the node. Only a peek can make that clone necessary, so the
+// first peek tells the list how to clone, and `pop` needs no `T: Clone`.
+pub struct List<T> {
+    head: AtomicPtr<Node<T>>,
+    len: AtomicUsize,
+    // Protects nodes while `pop` reads their `next` and `peek` their `elem`.
+    hazards: Domain,
+    // `T::clone`, once some `peek` has shown there is one.
+    clone: OnceLock<fn(&T) -> T>,
+    _boo: PhantomData<T>,
+}
+
+// Set in `Node::readers` once the node has been popped, so later peeks know
+// to leave it alone.
+const POPPED: usize = 1 << (usize::BITS - 1);
+
+// The node owns `elem` until a pop moves it out, and drops it itself if
+// nobody does, whether it's freed by the list or by the hazard domain.
+struct Node<T> {
+    elem: ManuallyDrop<T>,
+    next: *mut Node<T>,
+    // How many peeks are cloning `elem`, plus `POPPED`.
+    readers: AtomicUsize,
+    has_elem: AtomicBool,
+}
+
+impl<T> Drop for Node<T> {
+    fn drop(&mut self) {
+        if *self.has_elem.get_mut() {
+            unsafe { ManuallyDrop::drop(&mut self.elem) };
+        }
+    }
+}
+
+// Popping hands a T to another thread, so T: Send is all `&List` needs for
+// push and pop. `peek` shares &T across threads and asks for T: Sync itself.
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Send> Sync for List<T> {}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        List {
+            head: AtomicPtr::new(ptr::null_mut()),
+            len: AtomicUsize::new(0),
+            hazards: Domain::new(),
+            clone: OnceLock::new(),
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn push(&self, elem: T) {
+        let new_node = Box::into_raw(Box::new(Node {
+            elem: ManuallyDrop::new(elem),
+            next: ptr::null_mut(),
+            readers: AtomicUsize::new(0),
+            has_elem: AtomicBool::new(true),
+        }));
+
+        // Count the node before publishing it: once the CAS succeeds another
+        // thread can pop it and subtract it straight away, and if that came
+        // first `len` would wrap.
+        self.len.fetch_add(1, Relaxed);
+
+        // Nobody can see the new node until the CAS succeeds, so its `next`
+        // can be written without any care.
+        let mut head = self.head.load(Relaxed);
+        loop {
+            unsafe { (*new_node).next = head };
+            match self
+                .head
+                .compare_exchange_weak(head, new_node, Release, Relaxed)
+            {
+                Ok(_) => break,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let hazard = self.hazards.hazard();
+        loop {
+            let head = hazard.protect(&self.head);
+            if head.is_null() {
+                return None;
+            }
+
+            // `head` can't be freed while we're protecting it, and a node's
+            // `next` never changes once it's been pushed.
+            let next = unsafe { (*head).next };
+            if self
+                .head
+                .compare_exchange(head, next, AcqRel, Acquire)
+                .is_ok()
+            {
+                hazard.reset();
+                self.len.fetch_sub(1, Relaxed);
+
+                // The node is ours now, but a `peek` that got to it first may
+                // still be cloning the element. If so we clone it too and
+                // leave the original to the node (see the top of the file);
+                // if not, `POPPED` turns away any peek still to come, and the
+                // element can be moved out.
+                unsafe {
+                    let elem = if (*head).readers.fetch_or(POPPED, AcqRel) == 0 {
+                        (*head).has_elem.store(false, Relaxed);
+                        ManuallyDrop::into_inner(ptr::read(&(*head).elem))
+                    } else {
+                        let clone = self.clone.get().expect("only a peek reads elements");
+                        clone(&(*head).elem)
+                    };
+                    self.hazards.retire(head);
+                    return Some(elem);
+                }
+            }
+        }
+    }
+
+    // The top element can be popped by another thread the moment we've seen
+    // it, so there's no handing out a reference; you get a copy.
+    pub fn peek(&self) -> Option<T>
+    where
+        T: Clone + Sync,
+    {
+        // Before we count ourselves as a reader below, which is what
+        // publishes this to a pop that sees us.
+        self.clone.get_or_init(|| T::clone);
+        let hazard = self.hazards.hazard();
+        loop {
+            let head = hazard.protect(&self.head);
+            if head.is_null() {
+                return None;
+            }
+            unsafe {
+                if (*head).readers.fetch_add(1, AcqRel) & POPPED != 0 {
+                    // Popped since we protected it; the element may be gone.
+                    continue;
+                }
+                let elem = (*(*head).elem).clone();
+                (*head).readers.fetch_sub(1, Release);
+                return Some(elem);
+            }
+        }
+    }
+
+    // Only a snapshot: other threads may push or pop before you use it, and
+    // a push that's still publishing its node is already counted.
+    pub fn len(&self) -> usize {
+        self.len.load(Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Acquire).is_null()
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// `&mut self` means no other thread can be in the middle of anything, so the
+// remaining nodes can be freed directly. Nodes already popped belong to the
+// domain, which frees them when it's dropped right after this.
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        let mut cur = *self.head.get_mut();
+        while !cur.is_null() {
+            let node = unsafe { Box::from_raw(cur) };
+            cur = node.next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::List;
+    use crate::test_util::{Drops, Stuck};
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicBool, Ordering::*};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    const THREADS: usize = 8;
+    const PER_THREAD: usize = 10_000;
+
+    #[test]
+    fn basics() {
+        let list = List::new();
+
+        // Check empty list behaves right
+        assert_eq!(list.pop(), None);
+
+        // Populate list
+        list.push(1);
+        list.push(2);
+        list.push(3);
+
+        // Check normal removal
+        assert_eq!(list.pop(), Some(3));
+        assert_eq!(list.pop(), Some(2));
+
+        // Push some more just to make sure nothing's corrupted
+        list.push(4);
+        list.push(5);
+
+        // Check normal removal
+        assert_eq!(list.pop(), Some(5));
+        assert_eq!(list.pop(), Some(4));
+
+        // Check exhaustion
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(list.pop(), None);
+    }
+
+    #[test]
+    fn peek() {
+        let list = List::new();
+        assert_eq!(list.peek(), None);
+        assert!(list.is_empty());
+
+        list.push(1);
+        list.push(2);
+        assert_eq!(list.peek(), Some(2));
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.pop(), Some(2));
+        assert_eq!(list.peek(), Some(1));
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn drops_every_element_once() {
+        let drops = Drops::new();
+        let list = List::new();
+        for _ in 0..200 {
+            list.push(drops.track());
+        }
+        for _ in 0..150 {
+            drop(list.pop());
+        }
+        assert_eq!(drops.dropped(), 150);
+        drop(list);
+        assert_eq!(drops.dropped(), 200);
+    }
+
+    // The original goes with its node once the pop has cloned it.
+    #[test]
+    fn pop_does_not_wait_for_peek() {
+        let stuck = Stuck::new();
+        let list = Arc::new(List::new());
+        list.push(stuck.share());
+
+        let peeker = {
+            let list = list.clone();
+            thread::spawn(move || list.peek().is_some())
+        };
+        stuck.wait_halfway();
+        let popped = list.pop();
+        assert!(popped.is_some());
+        assert!(list.is_empty());
+        stuck.release();
+        assert!(peeker.join().unwrap());
+
+        drop(popped);
+        drop(list);
+        assert_eq!(stuck.alive(), 1);
+    }
+
+    // Every thread pushes its own range of numbers while popping; between
+    // what got popped and what's left, every number must show up once.
+    #[test]
+    fn stress_push_pop() {
+        let list = Arc::new(List::new());
+        let barrier = Arc::new(Barrier::new(THREADS));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let list = list.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    let mut popped = Vec::new();
+                    for i in 0..PER_THREAD {
+                        list.push(t * PER_THREAD + i);
+                        if i % 3 != 0 {
+                            popped.extend(list.pop());
+                        }
+                    }
+                    popped
+                })
+            })
+            .collect();
+
+        let mut seen = HashSet::new();
+        for handle in handles {
+            for elem in handle.join().unwrap() {
+                assert!(seen.insert(elem), "{} popped twice", elem);
+            }
+        }
+        while let Some(elem) = list.pop() {
+            assert!(seen.insert(elem), "{} popped twice", elem);
+        }
+        assert_eq!(seen.len(), THREADS * PER_THREAD);
+        assert_eq!(list.len(), 0);
+    }
+
+    // `len` is read while pushes and pops race each other; an element popped
+    // before its push was counted would show up as a wrapped counter.
+    #[test]
+    fn len_never_wraps() {
+        let list = Arc::new(List::new());
+        let done = Arc::new(AtomicBool::new(false));
+        let watcher = {
+            let list = list.clone();
+            let done = done.clone();
+            thread::spawn(move || {
+                while !done.load(SeqCst) {
+                    let len = list.len();
+                    assert!(len <= THREADS * PER_THREAD, "len wrapped to {}", len);
+                }
+            })
+        };
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let list = list.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_THREAD {
+                        list.push(i);
+                        list.pop();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        done.store(true, SeqCst);
+        watcher.join().unwrap();
+    }
+
+    // Peeking clones `Arc`s out of nodes other threads are busy popping and
+    // dropping; a peek that read a freed node would show up as a refcount
+    // that's off, or as a crash.
+    #[test]
+    fn stress_peek_while_popping() {
+        let list = Arc::new(List::new());
+        let barrier = Arc::new(Barrier::new(THREADS));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let list = list.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..PER_THREAD {
+                        if t % 2 == 0 {
+                            list.push(Arc::new(i));
+                            drop(list.pop());
+                        } else if let Some(elem) = list.peek() {
+                            assert!(*elem < PER_THREAD);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        while let Some(elem) = list.pop() {
+            assert_eq!(Arc::strong_count(&elem), 1);
+        }
+    }
+}